@@ -0,0 +1 @@
+-- Add migration script here
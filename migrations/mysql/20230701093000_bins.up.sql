@@ -0,0 +1,27 @@
+-- Add migration script here
+CREATE TABLE bins (
+	id CHAR(36) NOT NULL PRIMARY KEY,
+	name VARCHAR(255) NOT NULL,
+	status_code SMALLINT UNSIGNED NOT NULL DEFAULT 200,
+	headers JSON NOT NULL,
+	body MEDIUMTEXT,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMP NULL DEFAULT NULL,
+	INDEX bins_name_index (name),
+	INDEX deleted_at_index (deleted_at)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+
+CREATE TABLE trapped_requests (
+	id CHAR(36) NOT NULL PRIMARY KEY,
+	bin_id CHAR(36) NOT NULL,
+	method VARCHAR(10) NOT NULL,
+	path VARCHAR(2048) NOT NULL,
+	query TEXT,
+	version VARCHAR(16) NOT NULL,
+	headers JSON NOT NULL,
+	body LONGBLOB NOT NULL,
+	remote_addr VARCHAR(64) NOT NULL,
+	trapped_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	INDEX trapped_requests_bin_id_index (bin_id, trapped_at)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
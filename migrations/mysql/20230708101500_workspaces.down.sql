@@ -0,0 +1,10 @@
+-- Add migration script here
+ALTER TABLE executions
+	DROP INDEX executions_workspace_id_index,
+	DROP COLUMN workspace_id;
+
+ALTER TABLE requests
+	DROP INDEX requests_workspace_id_index,
+	DROP COLUMN workspace_id;
+
+DROP TABLE workspaces;
@@ -0,0 +1,22 @@
+-- Add migration script here
+CREATE TABLE workspaces (
+	id CHAR(36) NOT NULL PRIMARY KEY,
+	name VARCHAR(255) NOT NULL,
+	description TEXT,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMP NULL DEFAULT NULL,
+	INDEX workspaces_name_index (name),
+	INDEX deleted_at_index (deleted_at)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+
+INSERT INTO workspaces (id, name, description)
+VALUES ('00000000-0000-0000-0000-000000000000', 'default', 'the default workspace');
+
+ALTER TABLE requests
+	ADD COLUMN workspace_id CHAR(36) NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000' AFTER id,
+	ADD INDEX requests_workspace_id_index (workspace_id);
+
+ALTER TABLE executions
+	ADD COLUMN workspace_id CHAR(36) NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000' AFTER id,
+	ADD INDEX executions_workspace_id_index (workspace_id);
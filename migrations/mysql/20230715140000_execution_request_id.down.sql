@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE executions
+	DROP INDEX executions_request_id_index,
+	DROP COLUMN request_id;
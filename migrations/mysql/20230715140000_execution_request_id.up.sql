@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE executions
+	ADD COLUMN request_id CHAR(36) NOT NULL AFTER workspace_id,
+	ADD INDEX executions_request_id_index (request_id);
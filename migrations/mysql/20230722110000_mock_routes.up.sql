@@ -0,0 +1,21 @@
+-- Add migration script here
+CREATE TABLE mock_routes (
+	id CHAR(36) NOT NULL PRIMARY KEY,
+	name VARCHAR(255) NOT NULL,
+	method VARCHAR(10) NOT NULL DEFAULT '*',
+	path VARCHAR(2048) NOT NULL,
+	query JSON NOT NULL,
+	headers JSON NOT NULL,
+	body JSON,
+	priority INT NOT NULL DEFAULT 0,
+	status_code SMALLINT UNSIGNED NOT NULL DEFAULT 200,
+	response_headers JSON NOT NULL,
+	response_body MEDIUMTEXT,
+	delay_ms INT UNSIGNED NOT NULL DEFAULT 0,
+	hits BIGINT NOT NULL DEFAULT 0,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMP NULL DEFAULT NULL,
+	INDEX mock_routes_method_index (method, priority),
+	INDEX deleted_at_index (deleted_at)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
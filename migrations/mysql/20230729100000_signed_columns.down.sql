@@ -0,0 +1,7 @@
+-- Add migration script here
+ALTER TABLE mock_routes
+	MODIFY status_code SMALLINT UNSIGNED NOT NULL DEFAULT 200,
+	MODIFY delay_ms INT UNSIGNED NOT NULL DEFAULT 0;
+
+ALTER TABLE bins
+	MODIFY status_code SMALLINT UNSIGNED NOT NULL DEFAULT 200;
@@ -0,0 +1,7 @@
+-- Add migration script here
+ALTER TABLE bins
+	MODIFY status_code INT NOT NULL DEFAULT 200;
+
+ALTER TABLE mock_routes
+	MODIFY status_code INT NOT NULL DEFAULT 200,
+	MODIFY delay_ms BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,15 @@
+-- Add migration script here
+CREATE TABLE environments (
+	id CHAR(36) NOT NULL PRIMARY KEY,
+	workspace_id CHAR(36) NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	variables JSON NOT NULL,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMP NULL DEFAULT NULL,
+	INDEX environments_workspace_id_index (workspace_id),
+	INDEX deleted_at_index (deleted_at)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+
+ALTER TABLE executions
+	ADD COLUMN environment_id CHAR(36) NULL AFTER request_id;
@@ -0,0 +1,7 @@
+-- Add migration script here
+ALTER TABLE executions
+	DROP COLUMN assertions,
+	DROP COLUMN passed;
+
+ALTER TABLE requests
+	DROP COLUMN assertions;
@@ -0,0 +1,13 @@
+-- Add migration script here
+ALTER TABLE requests
+	ADD COLUMN assertions JSON NULL;
+UPDATE requests SET assertions = JSON_ARRAY();
+ALTER TABLE requests
+	MODIFY assertions JSON NOT NULL;
+
+ALTER TABLE executions
+	ADD COLUMN passed BOOLEAN NOT NULL DEFAULT TRUE,
+	ADD COLUMN assertions JSON NULL;
+UPDATE executions SET assertions = JSON_ARRAY();
+ALTER TABLE executions
+	MODIFY assertions JSON NOT NULL;
@@ -0,0 +1,27 @@
+-- Add migration script here
+CREATE TABLE collections (
+	id CHAR(36) NOT NULL PRIMARY KEY,
+	workspace_id CHAR(36) NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	description TEXT NULL,
+	requests JSON NOT NULL,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMP NULL DEFAULT NULL,
+	INDEX collections_workspace_id_index (workspace_id),
+	INDEX deleted_at_index (deleted_at)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+
+CREATE TABLE collection_runs (
+	id CHAR(36) NOT NULL PRIMARY KEY,
+	workspace_id CHAR(36) NOT NULL,
+	collection_id CHAR(36) NOT NULL,
+	environment_id CHAR(36) NULL,
+	stop_on_failure BOOLEAN NOT NULL,
+	passed BOOLEAN NOT NULL,
+	started_at TIMESTAMP(3) NOT NULL,
+	finished_at TIMESTAMP(3) NOT NULL,
+	steps JSON NOT NULL,
+	INDEX collection_runs_workspace_id_index (workspace_id),
+	INDEX collection_runs_collection_id_index (collection_id)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
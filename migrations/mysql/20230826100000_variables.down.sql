@@ -0,0 +1,8 @@
+-- Add migration script here
+ALTER TABLE executions
+	DROP COLUMN extracted;
+
+ALTER TABLE requests
+	DROP COLUMN extractors;
+
+DROP TABLE variables;
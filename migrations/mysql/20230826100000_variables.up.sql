@@ -0,0 +1,25 @@
+-- Add migration script here
+CREATE TABLE variables (
+	id CHAR(36) NOT NULL PRIMARY KEY,
+	workspace_id CHAR(36) NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	value TEXT NOT NULL,
+	execution_id CHAR(36) NULL,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMP NULL DEFAULT NULL,
+	INDEX variables_workspace_id_name_index (workspace_id, name),
+	INDEX deleted_at_index (deleted_at)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+
+ALTER TABLE requests
+	ADD COLUMN extractors JSON NULL;
+UPDATE requests SET extractors = JSON_ARRAY();
+ALTER TABLE requests
+	MODIFY extractors JSON NOT NULL;
+
+ALTER TABLE executions
+	ADD COLUMN extracted JSON NULL;
+UPDATE executions SET extracted = JSON_OBJECT();
+ALTER TABLE executions
+	MODIFY extracted JSON NOT NULL;
@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE requests
+	ADD COLUMN body_kind VARCHAR(16) NOT NULL DEFAULT 'json';
+
+ALTER TABLE raw_http_requests
+	ADD COLUMN body_kind VARCHAR(16) NOT NULL DEFAULT 'json';
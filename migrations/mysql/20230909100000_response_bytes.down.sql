@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE raw_http_responses
+	DROP COLUMN raw_body,
+	DROP COLUMN content_type,
+	DROP COLUMN size;
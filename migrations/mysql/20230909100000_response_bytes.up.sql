@@ -0,0 +1,11 @@
+-- Add migration script here
+ALTER TABLE raw_http_responses
+	ADD COLUMN size BIGINT NOT NULL DEFAULT 0,
+	ADD COLUMN content_type VARCHAR(255) NOT NULL DEFAULT '',
+	ADD COLUMN raw_body LONGBLOB NULL;
+UPDATE raw_http_responses SET
+	raw_body = IF(JSON_TYPE(body) = 'STRING', JSON_UNQUOTE(body), CAST(body AS CHAR)),
+	content_type = COALESCE(JSON_UNQUOTE(JSON_EXTRACT(headers, '$."content-type"')), '');
+UPDATE raw_http_responses SET size = LENGTH(raw_body);
+ALTER TABLE raw_http_responses
+	MODIFY raw_body LONGBLOB NOT NULL;
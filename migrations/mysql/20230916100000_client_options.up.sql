@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE requests
+	ADD COLUMN client_options JSON NULL;
+UPDATE requests SET client_options = JSON_OBJECT();
+ALTER TABLE requests
+	MODIFY client_options JSON NOT NULL;
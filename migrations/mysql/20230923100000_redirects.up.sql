@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE executions
+	ADD COLUMN redirects JSON NULL;
+UPDATE executions SET redirects = JSON_ARRAY();
+ALTER TABLE executions
+	MODIFY redirects JSON NOT NULL;
@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE executions
+	DROP COLUMN remote_addr,
+	DROP COLUMN timings;
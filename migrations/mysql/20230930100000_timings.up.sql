@@ -0,0 +1,7 @@
+-- Add migration script here
+ALTER TABLE executions
+	ADD COLUMN timings JSON NULL,
+	ADD COLUMN remote_addr VARCHAR(64) NULL;
+UPDATE executions SET timings = JSON_OBJECT();
+ALTER TABLE executions
+	MODIFY timings JSON NOT NULL;
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE requests
+	ADD COLUMN auth JSON NULL;
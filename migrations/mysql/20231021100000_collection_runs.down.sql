@@ -0,0 +1,6 @@
+-- Add migration script here
+UPDATE collection_runs SET finished_at = started_at WHERE finished_at IS NULL;
+
+ALTER TABLE collection_runs
+	DROP COLUMN cookie_jar_id,
+	MODIFY COLUMN finished_at TIMESTAMP(3) NOT NULL;
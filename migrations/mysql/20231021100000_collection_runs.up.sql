@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE collection_runs
+	MODIFY COLUMN finished_at TIMESTAMP(3) NULL,
+	ADD COLUMN cookie_jar_id CHAR(36) NULL AFTER environment_id;
@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE variables
+	DROP INDEX variables_workspace_id_name_index,
+	ADD INDEX variables_workspace_id_name_index (workspace_id, name);
@@ -0,0 +1,9 @@
+-- Add migration script here
+-- of the variables sharing a name, the one updated last is kept
+DELETE v FROM variables v
+JOIN variables w ON v.workspace_id = w.workspace_id AND v.name = w.name
+	AND (v.updated_at < w.updated_at OR (v.updated_at = w.updated_at AND v.id < w.id));
+
+ALTER TABLE variables
+	DROP INDEX variables_workspace_id_name_index,
+	ADD UNIQUE INDEX variables_workspace_id_name_index (workspace_id, name);
@@ -0,0 +1,28 @@
+-- the tables of the baseline executions migration, which is empty as they
+-- were created by hand. they are created before the migrations run so that
+-- a new database can be migrated, an existing one is left as it is.
+CREATE TABLE IF NOT EXISTS raw_http_requests (
+	id CHAR(36) NOT NULL PRIMARY KEY,
+	method VARCHAR(10) NOT NULL,
+	url TEXT NOT NULL,
+	version VARCHAR(16) NOT NULL,
+	headers JSON NOT NULL,
+	body JSON
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS raw_http_responses (
+	id CHAR(36) NOT NULL PRIMARY KEY,
+	version VARCHAR(16) NOT NULL,
+	status_code INT NOT NULL,
+	status_message VARCHAR(255) NOT NULL,
+	headers JSON NOT NULL,
+	body JSON NOT NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS executions (
+	id CHAR(36) NOT NULL PRIMARY KEY,
+	request CHAR(36) NOT NULL,
+	request_time TIMESTAMP(3) NOT NULL,
+	response_time TIMESTAMP(3) NOT NULL,
+	response CHAR(36) NOT NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
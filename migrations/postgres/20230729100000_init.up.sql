@@ -0,0 +1,106 @@
+-- Add migration script here
+CREATE TABLE workspaces (
+	id UUID NOT NULL PRIMARY KEY,
+	name VARCHAR(255) NOT NULL,
+	description TEXT,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMPTZ NULL DEFAULT NULL
+);
+CREATE INDEX workspaces_name_index ON workspaces (name);
+
+INSERT INTO workspaces (id, name, description)
+VALUES ('00000000-0000-0000-0000-000000000000', 'default', 'the default workspace');
+
+CREATE TABLE requests (
+	id UUID NOT NULL PRIMARY KEY,
+	workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000',
+	name VARCHAR(255) NOT NULL,
+	method VARCHAR(10) NOT NULL,
+	path VARCHAR(2048) NOT NULL,
+	query JSONB NOT NULL,
+	host VARCHAR(255) NOT NULL,
+	headers JSONB NOT NULL,
+	body JSONB,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMPTZ NULL DEFAULT NULL
+);
+CREATE INDEX requests_name_index ON requests (name);
+CREATE INDEX requests_workspace_id_index ON requests (workspace_id);
+
+CREATE TABLE raw_http_requests (
+	id UUID NOT NULL PRIMARY KEY,
+	method VARCHAR(10) NOT NULL,
+	url TEXT NOT NULL,
+	version VARCHAR(16) NOT NULL,
+	headers JSONB NOT NULL,
+	body JSONB
+);
+
+CREATE TABLE raw_http_responses (
+	id UUID NOT NULL PRIMARY KEY,
+	version VARCHAR(16) NOT NULL,
+	status_code INTEGER NOT NULL,
+	status_message VARCHAR(255) NOT NULL,
+	headers JSONB NOT NULL,
+	body JSONB NOT NULL
+);
+
+CREATE TABLE executions (
+	id UUID NOT NULL PRIMARY KEY,
+	workspace_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000',
+	request_id UUID NOT NULL,
+	request UUID NOT NULL,
+	request_time TIMESTAMPTZ NOT NULL,
+	response_time TIMESTAMPTZ NOT NULL,
+	response UUID NOT NULL
+);
+CREATE INDEX executions_workspace_id_index ON executions (workspace_id);
+CREATE INDEX executions_request_id_index ON executions (request_id);
+
+CREATE TABLE bins (
+	id UUID NOT NULL PRIMARY KEY,
+	name VARCHAR(255) NOT NULL,
+	status_code INTEGER NOT NULL DEFAULT 200,
+	headers JSONB NOT NULL,
+	body TEXT,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMPTZ NULL DEFAULT NULL
+);
+CREATE INDEX bins_name_index ON bins (name);
+
+CREATE TABLE trapped_requests (
+	id UUID NOT NULL PRIMARY KEY,
+	bin_id UUID NOT NULL,
+	method VARCHAR(10) NOT NULL,
+	path VARCHAR(2048) NOT NULL,
+	query TEXT,
+	version VARCHAR(16) NOT NULL,
+	headers JSONB NOT NULL,
+	body BYTEA NOT NULL,
+	remote_addr VARCHAR(64) NOT NULL,
+	trapped_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX trapped_requests_bin_id_index ON trapped_requests (bin_id, trapped_at);
+
+CREATE TABLE mock_routes (
+	id UUID NOT NULL PRIMARY KEY,
+	name VARCHAR(255) NOT NULL,
+	method VARCHAR(10) NOT NULL DEFAULT '*',
+	path VARCHAR(2048) NOT NULL,
+	query JSONB NOT NULL,
+	headers JSONB NOT NULL,
+	body JSONB,
+	priority INTEGER NOT NULL DEFAULT 0,
+	status_code INTEGER NOT NULL DEFAULT 200,
+	response_headers JSONB NOT NULL,
+	response_body TEXT,
+	delay_ms BIGINT NOT NULL DEFAULT 0,
+	hits BIGINT NOT NULL DEFAULT 0,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMPTZ NULL DEFAULT NULL
+);
+CREATE INDEX mock_routes_method_index ON mock_routes (method, priority);
@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE environments (
+	id UUID NOT NULL PRIMARY KEY,
+	workspace_id UUID NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	variables JSONB NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMPTZ NULL DEFAULT NULL
+);
+CREATE INDEX environments_workspace_id_index ON environments (workspace_id);
+
+ALTER TABLE executions ADD COLUMN environment_id UUID NULL;
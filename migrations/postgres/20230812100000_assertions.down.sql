@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE executions DROP COLUMN assertions;
+ALTER TABLE executions DROP COLUMN passed;
+
+ALTER TABLE requests DROP COLUMN assertions;
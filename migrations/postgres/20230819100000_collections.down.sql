@@ -0,0 +1,4 @@
+-- Add migration script here
+DROP TABLE collection_runs;
+
+DROP TABLE collections;
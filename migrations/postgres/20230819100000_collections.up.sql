@@ -0,0 +1,26 @@
+-- Add migration script here
+CREATE TABLE collections (
+	id UUID NOT NULL PRIMARY KEY,
+	workspace_id UUID NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	description TEXT NULL,
+	requests JSONB NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMPTZ NULL DEFAULT NULL
+);
+CREATE INDEX collections_workspace_id_index ON collections (workspace_id);
+
+CREATE TABLE collection_runs (
+	id UUID NOT NULL PRIMARY KEY,
+	workspace_id UUID NOT NULL,
+	collection_id UUID NOT NULL,
+	environment_id UUID NULL,
+	stop_on_failure BOOLEAN NOT NULL,
+	passed BOOLEAN NOT NULL,
+	started_at TIMESTAMPTZ NOT NULL,
+	finished_at TIMESTAMPTZ NOT NULL,
+	steps JSONB NOT NULL
+);
+CREATE INDEX collection_runs_workspace_id_index ON collection_runs (workspace_id);
+CREATE INDEX collection_runs_collection_id_index ON collection_runs (collection_id);
@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TABLE variables (
+	id UUID NOT NULL PRIMARY KEY,
+	workspace_id UUID NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	value TEXT NOT NULL,
+	execution_id UUID NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMPTZ NULL DEFAULT NULL
+);
+CREATE INDEX variables_workspace_id_name_index ON variables (workspace_id, name);
+
+ALTER TABLE requests ADD COLUMN extractors JSONB NOT NULL DEFAULT '[]';
+
+ALTER TABLE executions ADD COLUMN extracted JSONB NOT NULL DEFAULT '{}';
@@ -0,0 +1,8 @@
+-- Add migration script here
+ALTER TABLE raw_http_responses ADD COLUMN size BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE raw_http_responses ADD COLUMN content_type VARCHAR(255) NOT NULL DEFAULT '';
+ALTER TABLE raw_http_responses ADD COLUMN raw_body BYTEA NOT NULL DEFAULT '';
+UPDATE raw_http_responses SET
+	raw_body = convert_to(CASE WHEN jsonb_typeof(body) = 'string' THEN body #>> '{}' ELSE body::TEXT END, 'UTF8'),
+	content_type = COALESCE(headers ->> 'content-type', '');
+UPDATE raw_http_responses SET size = octet_length(raw_body);
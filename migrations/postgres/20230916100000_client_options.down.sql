@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE requests DROP COLUMN client_options;
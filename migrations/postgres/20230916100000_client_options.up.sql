@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE requests ADD COLUMN client_options JSONB NOT NULL DEFAULT '{}';
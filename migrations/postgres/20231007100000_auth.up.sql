@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE requests ADD COLUMN auth JSONB NULL;
@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE cookie_jars (
+	id UUID NOT NULL PRIMARY KEY,
+	workspace_id UUID NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	cookies JSONB NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMPTZ NULL DEFAULT NULL
+);
+CREATE INDEX cookie_jars_workspace_id_index ON cookie_jars (workspace_id);
+
+ALTER TABLE executions ADD COLUMN cookie_jar_id UUID NULL;
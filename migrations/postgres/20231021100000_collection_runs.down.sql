@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE collection_runs DROP COLUMN cookie_jar_id;
+
+UPDATE collection_runs SET finished_at = started_at WHERE finished_at IS NULL;
+
+ALTER TABLE collection_runs ALTER COLUMN finished_at SET NOT NULL;
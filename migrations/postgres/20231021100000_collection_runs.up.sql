@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE collection_runs ALTER COLUMN finished_at DROP NOT NULL;
+
+ALTER TABLE collection_runs ADD COLUMN cookie_jar_id UUID NULL;
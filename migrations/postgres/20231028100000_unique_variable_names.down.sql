@@ -0,0 +1,3 @@
+-- Add migration script here
+DROP INDEX variables_workspace_id_name_index;
+CREATE INDEX variables_workspace_id_name_index ON variables (workspace_id, name);
@@ -0,0 +1,8 @@
+-- Add migration script here
+-- of the variables sharing a name, the one updated last is kept
+DELETE FROM variables v USING variables w
+WHERE v.workspace_id = w.workspace_id AND v.name = w.name
+	AND (v.updated_at < w.updated_at OR (v.updated_at = w.updated_at AND v.id < w.id));
+
+DROP INDEX variables_workspace_id_name_index;
+CREATE UNIQUE INDEX variables_workspace_id_name_index ON variables (workspace_id, name);
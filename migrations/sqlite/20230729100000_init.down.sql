@@ -0,0 +1,9 @@
+-- Add migration script here
+DROP TABLE mock_routes;
+DROP TABLE trapped_requests;
+DROP TABLE bins;
+DROP TABLE executions;
+DROP TABLE raw_http_responses;
+DROP TABLE raw_http_requests;
+DROP TABLE requests;
+DROP TABLE workspaces;
@@ -0,0 +1,106 @@
+-- Add migration script here
+CREATE TABLE workspaces (
+	id TEXT NOT NULL PRIMARY KEY,
+	name TEXT NOT NULL,
+	description TEXT,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at DATETIME NULL DEFAULT NULL
+);
+CREATE INDEX workspaces_name_index ON workspaces (name);
+
+INSERT INTO workspaces (id, name, description)
+VALUES ('00000000-0000-0000-0000-000000000000', 'default', 'the default workspace');
+
+CREATE TABLE requests (
+	id TEXT NOT NULL PRIMARY KEY,
+	workspace_id TEXT NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000',
+	name TEXT NOT NULL,
+	method TEXT NOT NULL,
+	path TEXT NOT NULL,
+	query TEXT NOT NULL,
+	host TEXT NOT NULL,
+	headers TEXT NOT NULL,
+	body TEXT,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at DATETIME NULL DEFAULT NULL
+);
+CREATE INDEX requests_name_index ON requests (name);
+CREATE INDEX requests_workspace_id_index ON requests (workspace_id);
+
+CREATE TABLE raw_http_requests (
+	id TEXT NOT NULL PRIMARY KEY,
+	method TEXT NOT NULL,
+	url TEXT NOT NULL,
+	version TEXT NOT NULL,
+	headers TEXT NOT NULL,
+	body TEXT
+);
+
+CREATE TABLE raw_http_responses (
+	id TEXT NOT NULL PRIMARY KEY,
+	version TEXT NOT NULL,
+	status_code INTEGER NOT NULL,
+	status_message TEXT NOT NULL,
+	headers TEXT NOT NULL,
+	body TEXT NOT NULL
+);
+
+CREATE TABLE executions (
+	id TEXT NOT NULL PRIMARY KEY,
+	workspace_id TEXT NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000',
+	request_id TEXT NOT NULL,
+	request TEXT NOT NULL,
+	request_time DATETIME NOT NULL,
+	response_time DATETIME NOT NULL,
+	response TEXT NOT NULL
+);
+CREATE INDEX executions_workspace_id_index ON executions (workspace_id);
+CREATE INDEX executions_request_id_index ON executions (request_id);
+
+CREATE TABLE bins (
+	id TEXT NOT NULL PRIMARY KEY,
+	name TEXT NOT NULL,
+	status_code INTEGER NOT NULL DEFAULT 200,
+	headers TEXT NOT NULL,
+	body TEXT,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at DATETIME NULL DEFAULT NULL
+);
+CREATE INDEX bins_name_index ON bins (name);
+
+CREATE TABLE trapped_requests (
+	id TEXT NOT NULL PRIMARY KEY,
+	bin_id TEXT NOT NULL,
+	method TEXT NOT NULL,
+	path TEXT NOT NULL,
+	query TEXT,
+	version TEXT NOT NULL,
+	headers TEXT NOT NULL,
+	body BLOB NOT NULL,
+	remote_addr TEXT NOT NULL,
+	trapped_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX trapped_requests_bin_id_index ON trapped_requests (bin_id, trapped_at);
+
+CREATE TABLE mock_routes (
+	id TEXT NOT NULL PRIMARY KEY,
+	name TEXT NOT NULL,
+	method TEXT NOT NULL DEFAULT '*',
+	path TEXT NOT NULL,
+	query TEXT NOT NULL,
+	headers TEXT NOT NULL,
+	body TEXT,
+	priority INTEGER NOT NULL DEFAULT 0,
+	status_code INTEGER NOT NULL DEFAULT 200,
+	response_headers TEXT NOT NULL,
+	response_body TEXT,
+	delay_ms INTEGER NOT NULL DEFAULT 0,
+	hits INTEGER NOT NULL DEFAULT 0,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at DATETIME NULL DEFAULT NULL
+);
+CREATE INDEX mock_routes_method_index ON mock_routes (method, priority);
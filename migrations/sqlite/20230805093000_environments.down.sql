@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE executions DROP COLUMN environment_id;
+
+DROP TABLE environments;
@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE environments (
+	id TEXT NOT NULL PRIMARY KEY,
+	workspace_id TEXT NOT NULL,
+	name TEXT NOT NULL,
+	variables TEXT NOT NULL,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at DATETIME NULL DEFAULT NULL
+);
+CREATE INDEX environments_workspace_id_index ON environments (workspace_id);
+
+ALTER TABLE executions ADD COLUMN environment_id TEXT NULL;
@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE requests ADD COLUMN assertions TEXT NOT NULL DEFAULT '[]';
+
+ALTER TABLE executions ADD COLUMN passed BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE executions ADD COLUMN assertions TEXT NOT NULL DEFAULT '[]';
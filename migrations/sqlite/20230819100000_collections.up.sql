@@ -0,0 +1,26 @@
+-- Add migration script here
+CREATE TABLE collections (
+	id TEXT NOT NULL PRIMARY KEY,
+	workspace_id TEXT NOT NULL,
+	name TEXT NOT NULL,
+	description TEXT NULL,
+	requests TEXT NOT NULL,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at DATETIME NULL DEFAULT NULL
+);
+CREATE INDEX collections_workspace_id_index ON collections (workspace_id);
+
+CREATE TABLE collection_runs (
+	id TEXT NOT NULL PRIMARY KEY,
+	workspace_id TEXT NOT NULL,
+	collection_id TEXT NOT NULL,
+	environment_id TEXT NULL,
+	stop_on_failure BOOLEAN NOT NULL,
+	passed BOOLEAN NOT NULL,
+	started_at DATETIME NOT NULL,
+	finished_at DATETIME NOT NULL,
+	steps TEXT NOT NULL
+);
+CREATE INDEX collection_runs_workspace_id_index ON collection_runs (workspace_id);
+CREATE INDEX collection_runs_collection_id_index ON collection_runs (collection_id);
@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TABLE variables (
+	id TEXT NOT NULL PRIMARY KEY,
+	workspace_id TEXT NOT NULL,
+	name TEXT NOT NULL,
+	value TEXT NOT NULL,
+	execution_id TEXT NULL,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at DATETIME NULL DEFAULT NULL
+);
+CREATE INDEX variables_workspace_id_name_index ON variables (workspace_id, name);
+
+ALTER TABLE requests ADD COLUMN extractors TEXT NOT NULL DEFAULT '[]';
+
+ALTER TABLE executions ADD COLUMN extracted TEXT NOT NULL DEFAULT '{}';
@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE raw_http_requests DROP COLUMN body_kind;
+
+ALTER TABLE requests DROP COLUMN body_kind;
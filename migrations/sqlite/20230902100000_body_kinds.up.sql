@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE requests ADD COLUMN body_kind TEXT NOT NULL DEFAULT 'json';
+
+ALTER TABLE raw_http_requests ADD COLUMN body_kind TEXT NOT NULL DEFAULT 'json';
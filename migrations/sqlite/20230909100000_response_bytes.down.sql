@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE raw_http_responses DROP COLUMN raw_body;
+ALTER TABLE raw_http_responses DROP COLUMN content_type;
+ALTER TABLE raw_http_responses DROP COLUMN size;
@@ -0,0 +1,8 @@
+-- Add migration script here
+ALTER TABLE raw_http_responses ADD COLUMN size INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE raw_http_responses ADD COLUMN content_type TEXT NOT NULL DEFAULT '';
+ALTER TABLE raw_http_responses ADD COLUMN raw_body BLOB NOT NULL DEFAULT x'';
+UPDATE raw_http_responses SET
+	raw_body = CAST(CASE WHEN json_type(body) = 'text' THEN json_extract(body, '$') ELSE body END AS BLOB),
+	content_type = COALESCE(json_extract(headers, '$."content-type"'), '');
+UPDATE raw_http_responses SET size = length(raw_body);
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE executions ADD COLUMN redirects TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE executions ADD COLUMN timings TEXT NOT NULL DEFAULT '{}';
+ALTER TABLE executions ADD COLUMN remote_addr TEXT NULL;
@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE executions DROP COLUMN cookie_jar_id;
+
+DROP TABLE cookie_jars;
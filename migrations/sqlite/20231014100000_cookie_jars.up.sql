@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE cookie_jars (
+	id TEXT NOT NULL PRIMARY KEY,
+	workspace_id TEXT NOT NULL,
+	name TEXT NOT NULL,
+	cookies TEXT NOT NULL,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	deleted_at DATETIME NULL DEFAULT NULL
+);
+CREATE INDEX cookie_jars_workspace_id_index ON cookie_jars (workspace_id);
+
+ALTER TABLE executions ADD COLUMN cookie_jar_id TEXT NULL;
@@ -0,0 +1,21 @@
+-- Add migration script here
+CREATE TABLE collection_runs_old (
+	id TEXT NOT NULL PRIMARY KEY,
+	workspace_id TEXT NOT NULL,
+	collection_id TEXT NOT NULL,
+	environment_id TEXT NULL,
+	stop_on_failure BOOLEAN NOT NULL,
+	passed BOOLEAN NOT NULL,
+	started_at DATETIME NOT NULL,
+	finished_at DATETIME NOT NULL,
+	steps TEXT NOT NULL
+);
+
+INSERT INTO collection_runs_old (id, workspace_id, collection_id, environment_id, stop_on_failure, passed, started_at, finished_at, steps)
+SELECT id, workspace_id, collection_id, environment_id, stop_on_failure, passed, started_at, COALESCE(finished_at, started_at), steps FROM collection_runs;
+
+DROP TABLE collection_runs;
+
+ALTER TABLE collection_runs_old RENAME TO collection_runs;
+CREATE INDEX collection_runs_workspace_id_index ON collection_runs (workspace_id);
+CREATE INDEX collection_runs_collection_id_index ON collection_runs (collection_id);
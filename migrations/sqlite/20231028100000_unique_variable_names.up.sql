@@ -0,0 +1,10 @@
+-- Add migration script here
+-- of the variables sharing a name, the one updated last is kept
+DELETE FROM variables WHERE EXISTS (
+	SELECT 1 FROM variables w
+	WHERE w.workspace_id = variables.workspace_id AND w.name = variables.name
+		AND (variables.updated_at < w.updated_at OR (variables.updated_at = w.updated_at AND variables.id < w.id))
+);
+
+DROP INDEX variables_workspace_id_name_index;
+CREATE UNIQUE INDEX variables_workspace_id_name_index ON variables (workspace_id, name);
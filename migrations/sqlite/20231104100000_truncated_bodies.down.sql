@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE raw_http_responses DROP COLUMN truncated;
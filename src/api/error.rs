@@ -6,17 +6,29 @@ use thiserror::Error;
 pub enum Error {
     #[error("unexpected rows affected, expected {0}, got {1}")]
     UnexpectedRowsAffected(u64, u64),
+    #[error("bad request: {0}")]
+    BadRequest(String),
     #[error("resource not found.")]
     NotFound,
     #[error("resource created failed: {0}")]
     CreateFailed(String),
+    #[error("invalid response: {0}")]
+    InvalidResponse(String),
     #[error("database error: {0}")]
-    DatabaseError(#[from] sqlx::Error),
+    Database(#[from] sqlx::Error),
 }
 
 impl Error {
+    // the error of a service creating something, passed through as it is when
+    // it is one of ours so that its status is kept.
+    pub(crate) fn create_failed(e: anyhow::Error) -> Self {
+        e.downcast::<Self>()
+            .unwrap_or_else(|e| Self::CreateFailed(e.to_string()))
+    }
+
     fn status(&self) -> u16 {
         match self {
+            Self::BadRequest(_) => 400,
             Self::NotFound => 404,
             _ => 500,
         }
@@ -28,3 +40,19 @@ impl IntoResponse for Error {
         resp::Response::error(self.status(), self).into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_failed_keeps_our_errors() {
+        let e = Error::create_failed(Error::NotFound.into());
+        assert_eq!(e.status(), 404);
+        let e = Error::create_failed(Error::BadRequest("invalid".to_string()).into());
+        assert_eq!(e.status(), 400);
+        let e = Error::create_failed(anyhow::anyhow!("connection refused"));
+        assert!(matches!(e, Error::CreateFailed(ref message) if message == "connection refused"));
+        assert_eq!(e.status(), 500);
+    }
+}
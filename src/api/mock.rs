@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::{self, Bytes},
+    extract::{Path, Query},
+    http::{HeaderMap, Method},
+    response::IntoResponse,
+    routing::any,
+    Router,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    api::{error::Error, Result},
+    entity::mock::MockRoute,
+    service::mock::{self, MockRequest},
+};
+
+#[derive(Debug, Deserialize)]
+struct MockPath {
+    path: Option<String>,
+}
+
+pub(crate) fn router() -> Router {
+    Router::new()
+        .route("/", any(serve))
+        .route("/*path", any(serve))
+}
+
+async fn serve(
+    Path(MockPath { path }): Path<MockPath>,
+    Query(query): Query<HashMap<String, String>>,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<axum::response::Response> {
+    let path = format!("/{}", path.unwrap_or_default());
+    let request = MockRequest {
+        method: method.as_str(),
+        path: &path,
+        query: &query,
+        headers: &headers,
+        body: &body,
+    };
+    let route = mock::find_route(&request)
+        .await
+        .map_err(|e| Error::InvalidResponse(e.to_string()))?
+        .ok_or_else(|| Error::NotFound)?;
+    tracing::info!("mock {} {} answered by {}", method, path, route.id);
+    if route.delay_ms > 0 {
+        tokio::time::sleep(std::time::Duration::from_millis(route.delay_ms as u64)).await;
+    }
+    Ok(make_response(&route))
+}
+
+fn make_response(route: &MockRoute) -> axum::response::Response {
+    let mut builder = axum::response::Response::builder().status(route.status_code as u16);
+    if let Some(headers) = route.response_headers.as_object() {
+        for (k, v) in headers {
+            match v {
+                Value::String(s) => builder = builder.header(k, s),
+                other => builder = builder.header(k, other.to_string()),
+            }
+        }
+    }
+    let body = body::boxed(body::Full::from(
+        route.response_body.clone().unwrap_or_default(),
+    ));
+    builder
+        .body(body)
+        .unwrap_or_else(|e| Error::InvalidResponse(e.to_string()).into_response())
+}
@@ -1,5 +1,7 @@
 pub(crate) mod error;
+pub(crate) mod mock;
 pub(crate) mod resp;
+pub(crate) mod trap;
 #[macro_use]
 pub(crate) mod v1;
 
@@ -14,6 +16,9 @@ use tower_http::{
 
 pub(crate) type Result<T> = anyhow::Result<T, error::Error>;
 
+// how long the server takes to answer at most, longer requests are cut off.
+pub(crate) const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
 pub(crate) fn router() -> Router {
     // https://docs.rs/tower-http/0.4.0/tower_http/request_id/index.html
     let request_id = ServiceBuilder::new()
@@ -30,13 +35,14 @@ pub(crate) fn router() -> Router {
         )
         // propagate the header to the response before the response reaches `TraceLayer`
         .propagate_x_request_id();
-    let timeout =
-        ServiceBuilder::new().layer(TimeoutLayer::new(std::time::Duration::from_secs(10)));
+    let timeout = ServiceBuilder::new().layer(TimeoutLayer::new(TIMEOUT));
     let compress = ServiceBuilder::new().layer(tower_http::compression::CompressionLayer::new());
 
     // router
     Router::new()
         .nest("/api/v1", v1::router())
+        .nest("/trap", trap::router())
+        .nest("/mock", mock::router())
         .layer(request_id)
         .layer(timeout)
         .layer(compress)
@@ -2,7 +2,6 @@ use super::error::Error;
 use super::Result;
 use axum::{body, response::IntoResponse, Json};
 use serde::Serialize;
-use uuid::Uuid;
 
 // #[derive(Debug, Serialize)]
 // pub(crate) struct Created {
@@ -25,27 +24,6 @@ use uuid::Uuid;
 //     }
 // }
 
-#[derive(Debug, Serialize)]
-pub(crate) struct BatchCreated {
-    pub(crate) ids: Vec<Uuid>,
-}
-
-impl Into<BatchCreated> for Vec<Uuid> {
-    fn into(self) -> BatchCreated {
-        BatchCreated { ids: self }
-    }
-}
-
-impl IntoResponse for BatchCreated {
-    fn into_response(self) -> axum::response::Response {
-        let body = body::boxed(body::Full::from(serde_json::to_string(&self).unwrap()));
-        axum::response::Response::builder()
-            .status(201)
-            .body(body)
-            .unwrap()
-    }
-}
-
 // define the response for fetching one resource
 #[derive(Debug, Serialize)]
 pub(crate) struct FetchOne<T: Serialize> {
@@ -77,15 +55,12 @@ pub(crate) struct FetchPaged<T: Serialize> {
     pub(crate) data: Vec<T>,
 }
 
-impl<T> Into<FetchPaged<T>> for (i64, Vec<T>)
+impl<T> From<(i64, Vec<T>)> for FetchPaged<T>
 where
     T: Serialize,
 {
-    fn into(self) -> FetchPaged<T> {
-        FetchPaged {
-            total: self.0,
-            data: self.1,
-        }
+    fn from((total, data): (i64, Vec<T>)) -> FetchPaged<T> {
+        FetchPaged { total, data }
     }
 }
 
@@ -103,11 +78,9 @@ pub(crate) struct RowsAffected {
     pub(crate) rows_affected: u64,
 }
 
-impl Into<RowsAffected> for u64 {
-    fn into(self) -> RowsAffected {
-        RowsAffected {
-            rows_affected: self,
-        }
+impl From<u64> for RowsAffected {
+    fn from(rows_affected: u64) -> RowsAffected {
+        RowsAffected { rows_affected }
     }
 }
 
@@ -0,0 +1,92 @@
+use std::net::SocketAddr;
+
+use axum::{
+    body::{self, Bytes},
+    extract::{ConnectInfo, Path},
+    http::{HeaderMap, Method, Uri, Version},
+    response::IntoResponse,
+    routing::any,
+    Router,
+};
+use chrono::Local;
+use serde::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    api::{error::Error, resp::ExpectRowsAffected, Result},
+    entity::{
+        bin::{Bin, TrappedRequest},
+        id::Id,
+    },
+    service::execution::headers_value,
+    storage::{store, Storage},
+};
+
+#[derive(Debug, Deserialize)]
+struct TrapPath {
+    bin_id: Uuid,
+    path: Option<String>,
+}
+
+pub(crate) fn router() -> Router {
+    Router::new()
+        .route("/:bin_id", any(trap))
+        .route("/:bin_id/*path", any(trap))
+}
+
+async fn trap(
+    Path(TrapPath { bin_id, path }): Path<TrapPath>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    method: Method,
+    uri: Uri,
+    version: Version,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<axum::response::Response> {
+    let bin = store()
+        .by_id::<Bin>(bin_id.into())
+        .await?
+        .ok_or_else(|| Error::NotFound)?;
+    let trapped = TrappedRequest {
+        id: Id::new(),
+        bin_id: bin.id,
+        method: method.to_string(),
+        path: format!("/{}", path.unwrap_or_default()),
+        query: uri.query().map(str::to_string),
+        version: format!("{:?}", version),
+        headers: headers_value(&headers),
+        body: body.to_vec(),
+        remote_addr: remote_addr.to_string(),
+        trapped_at: Local::now(),
+    };
+    tracing::info!(
+        "trapped {} {} into bin {}",
+        trapped.method,
+        trapped.path,
+        bin.id
+    );
+    store()
+        .create(trapped)
+        .await
+        .map_err(|e| Error::CreateFailed(e.to_string()))?
+        .expect(1)?;
+    Ok(make_response(&bin))
+}
+
+// answer the trapped request with whatever the bin is configured to return.
+fn make_response(bin: &Bin) -> axum::response::Response {
+    let mut builder = axum::response::Response::builder().status(bin.status_code as u16);
+    if let Some(headers) = bin.headers.as_object() {
+        for (k, v) in headers {
+            match v {
+                Value::String(s) => builder = builder.header(k, s),
+                other => builder = builder.header(k, other.to_string()),
+            }
+        }
+    }
+    let body = body::boxed(body::Full::from(bin.body.clone().unwrap_or_default()));
+    builder
+        .body(body)
+        .unwrap_or_else(|e| Error::InvalidResponse(e.to_string()).into_response())
+}
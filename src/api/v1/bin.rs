@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Path, Query},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use sqlx_crud::Schema;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        error::Error,
+        resp::{ExpectRowsAffected, FetchPaged, RowsAffected},
+        Result,
+    },
+    create,
+    entity::{
+        bin::{Bin, TrappedRequest},
+        id::Id,
+    },
+    retrieve, retrieve_list, router,
+    service::event::{Action, Event},
+    storage::{store, Filter, Storage},
+    update,
+};
+
+use super::{QueryWith, UpdateWith};
+
+#[derive(Debug, Deserialize)]
+struct BinRequest {
+    name: String,
+    #[serde(deserialize_with = "super::status_code")]
+    status_code: u16,
+    headers: serde_json::Value,
+    body: Option<String>,
+}
+
+impl From<BinRequest> for Bin {
+    fn from(request: BinRequest) -> Bin {
+        Bin {
+            id: Id::new(),
+            name: request.name,
+            status_code: request.status_code.into(),
+            headers: request.headers,
+            body: request.body,
+            ..Default::default()
+        }
+    }
+}
+
+impl UpdateWith<BinRequest> for Bin {
+    fn update_with(mut self, request: BinRequest) -> Bin {
+        self.name = request.name;
+        self.status_code = request.status_code.into();
+        self.headers = request.headers;
+        self.body = request.body;
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct BinQuery {
+    pub(crate) name: Option<String>,
+    pub(crate) page: Option<usize>,
+    pub(crate) per_page: Option<usize>,
+}
+
+impl QueryWith<Bin> for BinQuery {
+    fn query_with(self, query: &mut Filter) {
+        if let Some(ref name) = self.name {
+            query.and_contains("name", name);
+        }
+    }
+}
+
+router!();
+create!(BinRequest, Bin);
+retrieve!(Bin);
+retrieve_list!(BinQuery, Bin);
+update!(BinRequest, Bin);
+
+// deleting a bin deletes the requests trapped in it as well, at once so that
+// none are left without their bin.
+async fn delete(Path(id): Path<Uuid>) -> Result<RowsAffected> {
+    let bin = store()
+        .by_id::<Bin>(id.into())
+        .await?
+        .ok_or_else(|| Error::NotFound)?;
+    let mut trapped = Filter::default();
+    trapped.and_eq("bin_id", bin.id);
+    let mut this = Filter::default();
+    this.and_eq("id", bin.id);
+    let rows_affected = store()
+        .delete_where(&[
+            (TrappedRequest::table_name(), trapped),
+            (Bin::table_name(), this),
+        ])
+        .await?;
+    // the last statement deletes the bin itself
+    let rows_affected = rows_affected
+        .last()
+        .copied()
+        .unwrap_or_default()
+        .expect(1)?;
+    Event::new(Bin::table_name(), Action::Deleted, bin.id.to_string(), &bin).publish();
+    Ok(rows_affected)
+}
@@ -0,0 +1,121 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use sqlx_crud::Schema;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        error::Error,
+        resp::{ExpectRowsAffected, FetchPaged, RowsAffected},
+        Result,
+    },
+    create, delete,
+    entity::{
+        collection::{Collection, CollectionRun},
+        id::Id,
+    },
+    retrieve, retrieve_list, service,
+    storage::{store, Storage},
+    update,
+};
+
+use super::{workspace::CurrentWorkspace, EntityPath, QueryWith, UpdateWith};
+
+#[derive(Debug, Deserialize)]
+struct CollectionRequest {
+    name: String,
+    description: Option<String>,
+    // the requests of the collection, in the order they are executed
+    #[serde(default)]
+    requests: Vec<Uuid>,
+}
+
+impl From<CollectionRequest> for Collection {
+    fn from(request: CollectionRequest) -> Collection {
+        Collection {
+            id: Id::new(),
+            name: request.name,
+            description: request.description,
+            requests: serde_json::json!(request.requests),
+            ..Default::default()
+        }
+    }
+}
+
+impl UpdateWith<CollectionRequest> for Collection {
+    fn update_with(mut self, request: CollectionRequest) -> Collection {
+        self.name = request.name;
+        self.description = request.description;
+        self.requests = serde_json::json!(request.requests);
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CollectionQuery {
+    pub(crate) name: Option<String>,
+    pub(crate) page: Option<usize>,
+    pub(crate) per_page: Option<usize>,
+}
+
+impl QueryWith<Collection> for CollectionQuery {
+    fn query_with(self, query: &mut crate::storage::Filter) {
+        if let Some(ref name) = self.name {
+            query.and_contains("name", name);
+        }
+    }
+}
+
+pub(crate) fn router() -> Router {
+    Router::new()
+        .route("/", get(retrieve_list).post(create))
+        .route("/:id", get(retrieve).put(update).delete(delete))
+        .route("/:id/run", post(run))
+}
+
+create!(CollectionRequest, Collection, scoped);
+retrieve!(Collection, scoped);
+retrieve_list!(CollectionQuery, Collection, scoped);
+update!(CollectionRequest, Collection, scoped);
+delete!(Collection, scoped);
+
+#[derive(Debug, Default, Deserialize)]
+struct RunRequest {
+    environment_id: Option<Uuid>,
+    cookie_jar_id: Option<Uuid>,
+    #[serde(default)]
+    stop_on_failure: bool,
+}
+
+// the body is optional, without it the requests run without an environment or
+// a cookie jar and all of them are executed. the run is returned once it has
+// started, its steps are added as the requests are executed.
+async fn run(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Path(EntityPath { id }): Path<EntityPath>,
+    body: Bytes,
+) -> Result<CollectionRun> {
+    let arg = match body.is_empty() {
+        true => RunRequest::default(),
+        false => serde_json::from_slice::<RunRequest>(&body)
+            .map_err(|e| Error::BadRequest(format!("invalid run: {}", e)))?,
+    };
+    let collection = store()
+        .by_id::<Collection>(id.into())
+        .await?
+        .filter(|collection| collection.workspace_id == workspace_id)
+        .ok_or_else(|| Error::NotFound)?;
+    service::collection::run_collection(
+        &collection,
+        arg.environment_id.map(Id::from),
+        arg.cookie_jar_id.map(Id::from),
+        arg.stop_on_failure,
+    )
+    .await
+    .map_err(Error::create_failed)
+}
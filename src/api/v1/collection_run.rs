@@ -0,0 +1,48 @@
+use axum::{
+    extract::{Path, Query},
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use sqlx_crud::Schema;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        resp::{ExpectRowsAffected, FetchPaged, RowsAffected},
+        Result,
+    },
+    delete,
+    entity::collection::CollectionRun,
+    retrieve, retrieve_list,
+    storage::Storage,
+};
+
+use super::{workspace::CurrentWorkspace, EntityPath, QueryWith};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CollectionRunQuery {
+    pub(crate) collection_id: Option<Uuid>,
+    pub(crate) page: Option<usize>,
+    pub(crate) per_page: Option<usize>,
+}
+
+impl QueryWith<CollectionRun> for CollectionRunQuery {
+    fn query_with(self, query: &mut crate::storage::Filter) {
+        if let Some(collection_id) = self.collection_id {
+            query.and_eq("collection_id", collection_id);
+        }
+        query.order_desc("started_at");
+    }
+}
+
+// runs are only created by running a collection, see `collection::run`.
+pub(crate) fn router() -> Router {
+    Router::new()
+        .route("/", get(retrieve_list))
+        .route("/:id", get(retrieve).delete(delete))
+}
+
+retrieve!(CollectionRun, scoped);
+retrieve_list!(CollectionRunQuery, CollectionRun, scoped);
+delete!(CollectionRun, scoped);
@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Path, Query},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use sqlx_crud::Schema;
+
+use crate::{
+    api::{
+        resp::{ExpectRowsAffected, FetchPaged, RowsAffected},
+        Result,
+    },
+    create, delete,
+    entity::{
+        cookie_jar::{Cookie, CookieJar},
+        id::Id,
+    },
+    retrieve, retrieve_list, router, service,
+    storage::Storage,
+};
+
+use super::{workspace::CurrentWorkspace, EntityPath, QueryWith};
+
+// the cookies of a jar are edited by replacing them, an empty list clears the
+// jar.
+#[derive(Debug, Deserialize)]
+struct CookieJarRequest {
+    name: String,
+    #[serde(default)]
+    cookies: Vec<Cookie>,
+}
+
+impl From<CookieJarRequest> for CookieJar {
+    fn from(request: CookieJarRequest) -> CookieJar {
+        CookieJar {
+            id: Id::new(),
+            name: request.name,
+            cookies: serde_json::json!(request.cookies),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CookieJarQuery {
+    pub(crate) name: Option<String>,
+    pub(crate) page: Option<usize>,
+    pub(crate) per_page: Option<usize>,
+}
+
+impl QueryWith<CookieJar> for CookieJarQuery {
+    fn query_with(self, query: &mut crate::storage::Filter) {
+        if let Some(ref name) = self.name {
+            query.and_contains("name", name);
+        }
+    }
+}
+
+router!();
+create!(CookieJarRequest, CookieJar, scoped);
+retrieve!(CookieJar, scoped);
+retrieve_list!(CookieJarQuery, CookieJar, scoped);
+delete!(CookieJar, scoped);
+
+// the jar is replaced while no execution writes back the cookies it was set.
+async fn update(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Path(EntityPath { id }): Path<EntityPath>,
+    Json(request): Json<CookieJarRequest>,
+) -> Result<RowsAffected> {
+    service::cookie::update_jar(workspace_id, id.into(), request.name, request.cookies).await?;
+    Ok(1.into())
+}
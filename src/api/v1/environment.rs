@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use sqlx_crud::Schema;
+
+use crate::{
+    api::{
+        resp::{ExpectRowsAffected, FetchPaged, RowsAffected},
+        Result,
+    },
+    create, delete,
+    entity::{environment::Environment, id::Id},
+    retrieve, retrieve_list, router,
+    storage::Storage,
+    update,
+};
+
+use super::{workspace::CurrentWorkspace, EntityPath, QueryWith, UpdateWith};
+
+#[derive(Debug, Deserialize)]
+struct EnvironmentRequest {
+    name: String,
+    #[serde(default)]
+    variables: HashMap<String, String>,
+}
+
+impl From<EnvironmentRequest> for Environment {
+    fn from(request: EnvironmentRequest) -> Environment {
+        Environment {
+            id: Id::new(),
+            name: request.name,
+            variables: serde_json::json!(request.variables),
+            ..Default::default()
+        }
+    }
+}
+
+impl UpdateWith<EnvironmentRequest> for Environment {
+    fn update_with(mut self, request: EnvironmentRequest) -> Environment {
+        self.name = request.name;
+        self.variables = serde_json::json!(request.variables);
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct EnvironmentQuery {
+    pub(crate) name: Option<String>,
+    pub(crate) page: Option<usize>,
+    pub(crate) per_page: Option<usize>,
+}
+
+impl QueryWith<Environment> for EnvironmentQuery {
+    fn query_with(self, query: &mut crate::storage::Filter) {
+        if let Some(ref name) = self.name {
+            query.and_contains("name", name);
+        }
+    }
+}
+
+router!();
+create!(EnvironmentRequest, Environment, scoped);
+retrieve!(Environment, scoped);
+retrieve_list!(EnvironmentQuery, Environment, scoped);
+update!(EnvironmentRequest, Environment, scoped);
+delete!(Environment, scoped);
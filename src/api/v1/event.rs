@@ -0,0 +1,127 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query,
+    },
+    response::{
+        sse::{self, KeepAlive, Sse},
+        IntoResponse,
+    },
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+use uuid::Uuid;
+
+use crate::{
+    entity::id::Id,
+    service::event::{self, Event, HISTORY_SIZE},
+};
+
+use super::workspace::CurrentWorkspace;
+
+#[derive(Debug, Deserialize)]
+struct EventQuery {
+    // only the events about this saved request and its executions
+    request_id: Option<Uuid>,
+    // replay up to this many of the latest events before the live ones
+    backfill: Option<usize>,
+}
+
+impl EventQuery {
+    fn subscribe(&self, workspace_id: Id) -> (Vec<Event>, broadcast::Receiver<Event>) {
+        event::subscribe(
+            self.backfill.unwrap_or(0).min(HISTORY_SIZE),
+            self.filter(workspace_id),
+        )
+    }
+
+    // the events of the workspace, and of the saved request when there is one.
+    fn filter(&self, workspace_id: Id) -> impl Fn(&Event) -> bool {
+        let request_id = self.request_id.map(|id| id.to_string());
+        move |event| {
+            event.visible_in(workspace_id)
+                && match request_id {
+                    Some(ref request_id) => event.concerns(request_id),
+                    None => true,
+                }
+        }
+    }
+}
+
+pub(crate) fn router() -> Router {
+    Router::new()
+        .route("/", get(server_sent_events))
+        .route("/ws", get(websocket))
+}
+
+async fn server_sent_events(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Query(query): Query<EventQuery>,
+) -> Sse<impl Stream<Item = Result<sse::Event, Infallible>>> {
+    let (backfill, receiver) = query.subscribe(workspace_id);
+    let live = BroadcastStream::new(receiver).filter_map(|event| match event {
+        Ok(event) => Some(event),
+        Err(e) => {
+            tracing::warn!("event subscriber lagged: {}", e);
+            None
+        }
+    });
+    let stream = tokio_stream::iter(backfill)
+        .chain(live.filter(query.filter(workspace_id)))
+        .map(|event| {
+            Ok(sse::Event::default()
+                .event(event.name())
+                .id(event.sequence.to_string())
+                .json_data(&event)
+                .unwrap_or_default())
+        });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn websocket(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    ws: WebSocketUpgrade,
+    Query(query): Query<EventQuery>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| forward(socket, workspace_id, query))
+}
+
+async fn forward(mut socket: WebSocket, workspace_id: Id, query: EventQuery) {
+    let (backfill, mut receiver) = query.subscribe(workspace_id);
+    let filter = query.filter(workspace_id);
+    for event in backfill.iter() {
+        if send(&mut socket, event).await.is_err() {
+            return;
+        }
+    }
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(event) if filter(&event) => {
+                    if send(&mut socket, &event).await.is_err() {
+                        return;
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(n)) => tracing::warn!("event subscriber lagged by {}", n),
+                Err(RecvError::Closed) => return,
+            },
+            // the client sends nothing we care about, but we have to notice it
+            // going away.
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+async fn send(socket: &mut WebSocket, event: &Event) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(event).unwrap();
+    socket.send(Message::Text(text)).await
+}
@@ -3,41 +3,45 @@ use chrono::Local;
 use chrono::DateTime;
 
 use axum::{
+    body,
     extract::{Path, Query},
+    http::header::CONTENT_TYPE,
+    response::IntoResponse,
     routing::get,
     Json, Router,
 };
 use serde::Deserialize;
 use serde::Serialize;
-use sqlx::Row;
-use sqlx_crud::{Crud, Schema};
-use uuid::fmt::Hyphenated;
+use serde_json::Value;
+use sqlx_crud::Schema;
 use uuid::Uuid;
 
+use crate::entity::assertion::AssertionResult;
+use crate::entity::execution::Hop;
 use crate::entity::execution::RawHttpRequest;
 use crate::entity::execution::RawHttpResponse;
+use crate::entity::execution::Timings;
+use crate::entity::id::Id;
 use crate::{
     api::{
         error,
-        resp::{ExpectRowsAffected, FetchPaged, RowsAffected},
+        resp::{ExpectRowsAffected, FetchOne, FetchPaged, RowsAffected},
         Result,
     },
-    db, delete, retrieve, retrieve_list, router, service,
+    delete, retrieve_list, service,
+    storage::{store, Storage},
 };
 
-use super::QueryWith;
+use super::{workspace::CurrentWorkspace, EntityPath, QueryWith};
 
 use crate::entity::execution::Execution;
+use crate::service::har::Har;
 
 #[derive(Debug, Clone, Deserialize)]
 struct ExecutionRequest {
     request_id: Uuid,
-}
-
-impl Into<Execution> for ExecutionRequest {
-    fn into(self) -> Execution {
-        unimplemented!()
-    }
+    environment_id: Option<Uuid>,
+    cookie_jar_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -48,42 +52,177 @@ struct ExecutionQuery {
 }
 
 impl QueryWith<Execution> for ExecutionQuery {
-    fn query_with(self, query: &mut sql_builder::SqlBuilder) {
-        query.and_where_eq("request_id", format!("'{}'", self.request_id));
+    fn query_with(self, query: &mut crate::storage::Filter) {
+        query.and_eq("request_id", self.request_id);
     }
 }
 
-router!();
+pub(crate) fn router() -> Router {
+    Router::new()
+        .route("/", get(retrieve_list).post(create))
+        .route("/:id", get(retrieve).put(update).delete(delete))
+        .route("/har", get(export_har))
+        .route("/:id/curl", get(export_curl))
+        .route("/:id/har", get(export_one_har))
+        .route("/:id/response/body", get(response_body))
+}
 
 #[derive(Debug, Serialize)]
 struct ExecutionRecord {
-    pub(crate) id: Hyphenated,
+    pub(crate) id: Id,
+    pub(crate) workspace_id: Id,
+    pub(crate) environment_id: Option<Id>,
+    pub(crate) cookie_jar_id: Option<Id>,
     pub(crate) request: RawHttpRequest,
     pub(crate) request_time: DateTime<Local>,
     pub(crate) response_time: DateTime<Local>,
     pub(crate) response: RawHttpResponse,
+    pub(crate) passed: bool,
+    pub(crate) assertions: Vec<AssertionResult>,
+    pub(crate) extracted: Value,
+    pub(crate) redirects: Vec<Hop>,
+    pub(crate) timings: Timings,
+    pub(crate) remote_addr: Option<String>,
+}
+
+impl IntoResponse for ExecutionRecord {
+    fn into_response(self) -> axum::response::Response {
+        FetchOne::new(self).into_response()
+    }
 }
 
-async fn create(Json(arg): Json<ExecutionRequest>) -> Result<ExecutionRecord> {
-    let request_id = arg.request_id.hyphenated();
-    let execution = service::execution::execute_request(request_id)
-        .await
-        .map_err(|e| error::Error::CreateFailed(e.to_string()))?;
+// the execution with the request it sent, its response and the redirects it
+// followed.
+async fn record(execution: Execution) -> Result<ExecutionRecord> {
     Ok(ExecutionRecord {
         id: execution.id,
-        request: RawHttpRequest::by_id(db::db_pool(), execution.request)
+        workspace_id: execution.workspace_id,
+        environment_id: execution.environment_id,
+        cookie_jar_id: execution.cookie_jar_id,
+        request: store()
+            .by_id::<RawHttpRequest>(execution.request)
             .await?
-            .unwrap(),
+            .ok_or_else(|| error::Error::NotFound)?,
         request_time: execution.request_time,
         response_time: execution.response_time,
-        response: RawHttpResponse::by_id(db::db_pool(), execution.response)
+        response: store()
+            .by_id::<RawHttpResponse>(execution.response)
             .await?
-            .unwrap(),
+            .ok_or_else(|| error::Error::NotFound)?,
+        passed: execution.passed,
+        assertions: serde_json::from_value(execution.assertions).unwrap_or_default(),
+        extracted: execution.extracted,
+        redirects: serde_json::from_value(execution.redirects).unwrap_or_default(),
+        timings: serde_json::from_value(execution.timings).unwrap_or_default(),
+        remote_addr: execution.remote_addr,
     })
 }
-retrieve!(Execution);
-retrieve_list!(ExecutionQuery, Execution);
-delete!(Execution);
+
+async fn create(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Json(arg): Json<ExecutionRequest>,
+) -> Result<ExecutionRecord> {
+    let request_id = arg.request_id.into();
+    let environment_id = arg.environment_id.map(Id::from);
+    let cookie_jar_id = arg.cookie_jar_id.map(Id::from);
+    let execution = service::execution::execute_request(
+        workspace_id,
+        request_id,
+        environment_id,
+        cookie_jar_id,
+    )
+    .await
+    .map_err(error::Error::create_failed)?;
+    record(execution).await
+}
+
+async fn retrieve(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Path(EntityPath { id }): Path<EntityPath>,
+) -> Result<ExecutionRecord> {
+    let execution = store()
+        .by_id::<Execution>(id.into())
+        .await?
+        .filter(|execution| execution.workspace_id == workspace_id)
+        .ok_or_else(|| error::Error::NotFound)?;
+    record(execution).await
+}
+retrieve_list!(ExecutionQuery, Execution, scoped);
+delete!(Execution, scoped);
+
+// the request exactly as it was sent by the execution.
+async fn export_curl(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Path(EntityPath { id }): Path<EntityPath>,
+) -> Result<String> {
+    let execution = store()
+        .by_id::<Execution>(id.into())
+        .await?
+        .filter(|execution| execution.workspace_id == workspace_id)
+        .ok_or_else(|| error::Error::NotFound)?;
+    let request = store()
+        .by_id::<RawHttpRequest>(execution.request)
+        .await?
+        .ok_or_else(|| error::Error::NotFound)?;
+    service::curl::render(&request).map_err(|e| error::Error::BadRequest(e.to_string()))
+}
+
+// the body of the response exactly as it was received.
+async fn response_body(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Path(EntityPath { id }): Path<EntityPath>,
+) -> Result<axum::response::Response> {
+    let execution = store()
+        .by_id::<Execution>(id.into())
+        .await?
+        .filter(|execution| execution.workspace_id == workspace_id)
+        .ok_or_else(|| error::Error::NotFound)?;
+    let response = store()
+        .by_id::<RawHttpResponse>(execution.response)
+        .await?
+        .ok_or_else(|| error::Error::NotFound)?;
+    let mut builder = axum::response::Response::builder();
+    if !response.content_type.is_empty() {
+        builder = builder.header(CONTENT_TYPE, response.content_type);
+    }
+    builder
+        .body(body::boxed(body::Full::from(response.raw_body)))
+        .map_err(|e| error::Error::InvalidResponse(e.to_string()))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HarQuery {
+    request_id: Option<Uuid>,
+    // the number of latest executions exported
+    limit: Option<usize>,
+}
+
+// the latest executions of the workspace as an HTTP Archive, oldest first.
+async fn export_har(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Query(query): Query<HarQuery>,
+) -> Result<Json<Har>> {
+    let har = service::har::export_latest(
+        workspace_id,
+        query.request_id.map(Id::from),
+        query.limit.unwrap_or(service::har::EXPORT_LIMIT),
+    )
+    .await?;
+    Ok(Json(har))
+}
+
+async fn export_one_har(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Path(EntityPath { id }): Path<EntityPath>,
+) -> Result<Json<Har>> {
+    let execution = store()
+        .by_id::<Execution>(id.into())
+        .await?
+        .filter(|execution| execution.workspace_id == workspace_id)
+        .ok_or_else(|| error::Error::NotFound)?;
+    let har = service::har::export(vec![execution]).await?;
+    Ok(Json(har))
+}
 
 async fn update() -> Result<()> {
     unimplemented!()
@@ -0,0 +1,164 @@
+use axum::{
+    extract::{Path, Query},
+    routing::{self, get},
+    Json, Router,
+};
+use serde::{de, Deserialize, Deserializer};
+use serde_json::Value;
+use sqlx_crud::Schema;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        resp::{ExpectRowsAffected, FetchPaged, RowsAffected},
+        Result, TIMEOUT,
+    },
+    create, delete,
+    entity::{id::Id, mock::MockRoute},
+    retrieve, retrieve_list,
+    service::{
+        event::{Action, Event},
+        mock,
+    },
+    storage::Storage,
+};
+
+use super::{QueryWith, UpdateWith};
+
+fn any_method() -> String {
+    "*".to_string()
+}
+
+fn empty_object() -> Value {
+    Value::Object(Default::default())
+}
+
+// a delay the server answers within, a longer one would be cut off.
+fn delay_ms<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<u32, D::Error> {
+    let delay_ms = u32::deserialize(deserializer)?;
+    let limit = TIMEOUT.as_millis();
+    if u128::from(delay_ms) >= limit {
+        return Err(de::Error::custom(format!(
+            "delay_ms {} is not below the limit of the server of {} ms",
+            delay_ms, limit
+        )));
+    }
+    Ok(delay_ms)
+}
+
+#[derive(Debug, Deserialize)]
+struct MockRouteRequest {
+    name: String,
+    #[serde(default = "any_method")]
+    method: String,
+    path: String,
+    #[serde(default = "empty_object")]
+    query: Value,
+    #[serde(default = "empty_object")]
+    headers: Value,
+    body: Option<Value>,
+    #[serde(default)]
+    priority: i32,
+    #[serde(deserialize_with = "super::status_code")]
+    status_code: u16,
+    #[serde(default = "empty_object")]
+    response_headers: Value,
+    response_body: Option<String>,
+    #[serde(default, deserialize_with = "delay_ms")]
+    delay_ms: u32,
+}
+
+impl From<MockRouteRequest> for MockRoute {
+    fn from(request: MockRouteRequest) -> MockRoute {
+        MockRoute {
+            id: Id::new(),
+            name: request.name,
+            method: request.method.to_uppercase(),
+            path: request.path,
+            query: request.query,
+            headers: request.headers,
+            body: request.body,
+            priority: request.priority,
+            status_code: request.status_code.into(),
+            response_headers: request.response_headers,
+            response_body: request.response_body,
+            delay_ms: request.delay_ms.into(),
+            ..Default::default()
+        }
+    }
+}
+
+// the hit counter is read-only, it is kept when a route is updated and only
+// reset with `DELETE /:id/hits`.
+impl UpdateWith<MockRouteRequest> for MockRoute {
+    fn update_with(mut self, request: MockRouteRequest) -> MockRoute {
+        self.name = request.name;
+        self.method = request.method.to_uppercase();
+        self.path = request.path;
+        self.query = request.query;
+        self.headers = request.headers;
+        self.body = request.body;
+        self.priority = request.priority;
+        self.status_code = request.status_code.into();
+        self.response_headers = request.response_headers;
+        self.response_body = request.response_body;
+        self.delay_ms = request.delay_ms.into();
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MockRouteQuery {
+    pub(crate) name: Option<String>,
+    pub(crate) path: Option<String>,
+    pub(crate) page: Option<usize>,
+    pub(crate) per_page: Option<usize>,
+}
+
+impl QueryWith<MockRoute> for MockRouteQuery {
+    fn query_with(self, query: &mut crate::storage::Filter) {
+        if let Some(ref name) = self.name {
+            query.and_contains("name", name);
+        }
+        if let Some(ref path) = self.path {
+            query.and_contains("path", path);
+        }
+    }
+}
+
+pub(crate) fn router() -> Router {
+    Router::new()
+        .route("/", get(retrieve_list).post(create))
+        .route("/:id", get(retrieve).put(update).delete(delete))
+        .route("/:id/hits", routing::delete(reset_hits))
+}
+
+create!(MockRouteRequest, MockRoute);
+retrieve!(MockRoute);
+retrieve_list!(MockRouteQuery, MockRoute);
+delete!(MockRoute);
+
+async fn update(
+    Path(id): Path<Uuid>,
+    Json(request): Json<MockRouteRequest>,
+) -> Result<RowsAffected> {
+    let route = mock::update_route(id.into(), |route| route.update_with(request)).await?;
+    publish(&route);
+    Ok(1.into())
+}
+
+async fn reset_hits(Path(id): Path<Uuid>) -> Result<RowsAffected> {
+    let route = mock::reset_hits(id.into()).await?;
+    publish(&route);
+    Ok(1.into())
+}
+
+fn publish(route: &MockRoute) {
+    Event::new(
+        MockRoute::table_name(),
+        Action::Updated,
+        route.id.to_string(),
+        route,
+    )
+    .publish();
+}
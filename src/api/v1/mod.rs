@@ -1,11 +1,48 @@
 use axum::Router;
+use serde::{de, Deserialize, Deserializer};
+use uuid::Uuid;
+pub(crate) mod bin;
+pub(crate) mod collection;
+pub(crate) mod collection_run;
+pub(crate) mod cookie_jar;
+pub(crate) mod environment;
+pub(crate) mod event;
 pub(crate) mod execution;
+pub(crate) mod mock;
 pub(crate) mod request;
+pub(crate) mod trapped;
+pub(crate) mod variable;
+pub(crate) mod workspace;
 
 pub(crate) fn router() -> Router {
     Router::new()
         .nest("/request", request::router())
         .nest("/execution", execution::router())
+        .nest("/bin", bin::router())
+        .nest("/trapped", trapped::router())
+        .nest("/workspace", workspace::router())
+        .nest("/events", event::router())
+        .nest("/mock", mock::router())
+        .nest("/environment", environment::router())
+        .nest("/collection", collection::router())
+        .nest("/collection-run", collection_run::router())
+        .nest("/variable", variable::router())
+        .nest("/cookie-jar", cookie_jar::router())
+        // the same resources, but with the workspace selected by path prefix
+        .nest("/workspace/:workspace_id/request", request::router())
+        .nest("/workspace/:workspace_id/execution", execution::router())
+        .nest(
+            "/workspace/:workspace_id/environment",
+            environment::router(),
+        )
+        .nest("/workspace/:workspace_id/collection", collection::router())
+        .nest(
+            "/workspace/:workspace_id/collection-run",
+            collection_run::router(),
+        )
+        .nest("/workspace/:workspace_id/variable", variable::router())
+        .nest("/workspace/:workspace_id/cookie-jar", cookie_jar::router())
+        .nest("/workspace/:workspace_id/events", event::router())
 }
 
 trait UpdateWith<T: Sized> {
@@ -13,5 +50,24 @@ trait UpdateWith<T: Sized> {
 }
 
 trait QueryWith<T: Sized> {
-    fn query_with(self, query: &mut sql_builder::SqlBuilder);
+    fn query_with(self, query: &mut crate::storage::Filter);
+}
+
+// the id of the entity in the path, other path parameters such as the
+// workspace id are ignored.
+#[derive(Debug, Deserialize)]
+struct EntityPath {
+    id: Uuid,
+}
+
+// the status code a bin or a mock answers with, from 100 to 599.
+fn status_code<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u16, D::Error> {
+    let status_code = u16::deserialize(deserializer)?;
+    if !(100..=599).contains(&status_code) {
+        return Err(de::Error::custom(format!(
+            "invalid status code {}, expected 100 to 599",
+            status_code
+        )));
+    }
+    Ok(status_code)
 }
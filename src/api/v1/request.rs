@@ -1,24 +1,30 @@
 use axum::{
     extract::{Path, Query},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use serde::Deserialize;
-use sqlx::Row;
-use sqlx_crud::{Crud, Schema};
+use sqlx_crud::Schema;
 use uuid::Uuid;
 
 use crate::{
     api::{
+        error::Error,
         resp::{ExpectRowsAffected, FetchPaged, RowsAffected},
         Result,
     },
-    create, db, delete,
-    entity::request::Request,
-    retrieve, retrieve_list, router, update,
+    create, delete,
+    entity::{
+        assertion::Assertion, auth::Auth, body::BodyKind, client::ClientOptions,
+        extractor::Extractor, id::Id, request::Request,
+    },
+    retrieve, retrieve_list, service,
+    service::{har::Har, import::Imported},
+    storage::{store, Storage},
+    update,
 };
 
-use super::{QueryWith, UpdateWith};
+use super::{workspace::CurrentWorkspace, EntityPath, QueryWith, UpdateWith};
 
 #[derive(Debug, Deserialize)]
 struct RequestRequest {
@@ -28,20 +34,35 @@ struct RequestRequest {
     query: serde_json::Value,
     host: String,
     headers: serde_json::Value,
+    #[serde(default)]
+    body_kind: BodyKind,
     body: Option<serde_json::Value>,
+    #[serde(default)]
+    assertions: Vec<Assertion>,
+    #[serde(default)]
+    extractors: Vec<Extractor>,
+    #[serde(default, deserialize_with = "ClientOptions::deserialize_checked")]
+    client_options: ClientOptions,
+    #[serde(default)]
+    auth: Option<Auth>,
 }
 
-impl Into<Request> for RequestRequest {
-    fn into(self) -> Request {
+impl From<RequestRequest> for Request {
+    fn from(request: RequestRequest) -> Request {
         Request {
-            id: uuid::Uuid::new_v4().hyphenated(),
-            name: self.name,
-            method: self.method,
-            path: self.path,
-            query: self.query,
-            host: self.host,
-            headers: self.headers,
-            body: self.body,
+            id: Id::new(),
+            name: request.name,
+            method: request.method,
+            path: request.path,
+            query: request.query,
+            host: request.host,
+            headers: request.headers,
+            body_kind: request.body_kind,
+            body: request.body,
+            assertions: serde_json::json!(request.assertions),
+            extractors: serde_json::json!(request.extractors),
+            client_options: serde_json::json!(request.client_options),
+            auth: request.auth.map(|auth| serde_json::json!(auth)),
             ..Default::default()
         }
     }
@@ -55,7 +76,12 @@ impl UpdateWith<RequestRequest> for Request {
         self.query = request.query;
         self.host = request.host;
         self.headers = request.headers;
+        self.body_kind = request.body_kind;
         self.body = request.body;
+        self.assertions = serde_json::json!(request.assertions);
+        self.extractors = serde_json::json!(request.extractors);
+        self.client_options = serde_json::json!(request.client_options);
+        self.auth = request.auth.map(|auth| serde_json::json!(auth));
         self
     }
 }
@@ -68,16 +94,114 @@ pub(crate) struct RequestQuery {
 }
 
 impl QueryWith<Request> for RequestQuery {
-    fn query_with(self, query: &mut sql_builder::SqlBuilder) {
+    fn query_with(self, query: &mut crate::storage::Filter) {
         if let Some(ref name) = self.name {
-            query.and_where_like("name", format!("%{}%", name));
+            query.and_contains("name", name);
         }
     }
 }
 
-router!();
-create!(RequestRequest, Request);
-retrieve!(Request);
-retrieve_list!(RequestQuery, Request);
-update!(RequestRequest, Request);
-delete!(Request);
+pub(crate) fn router() -> Router {
+    Router::new()
+        .route("/", get(retrieve_list).post(create))
+        .route("/:id", get(retrieve).put(update).delete(delete))
+        .route("/:id/curl", get(export_curl))
+        .route("/import/curl", post(import_curl))
+        .route("/import/har", post(import_har))
+        .route("/import/postman", post(import_postman))
+        .route("/import/openapi", post(import_openapi))
+}
+
+create!(RequestRequest, Request, scoped);
+retrieve!(Request, scoped);
+retrieve_list!(RequestQuery, Request, scoped);
+update!(RequestRequest, Request, scoped);
+delete!(Request, scoped);
+
+#[derive(Debug, Deserialize)]
+struct CurlImport {
+    command: String,
+    // defaults to the method and path of the request
+    name: Option<String>,
+}
+
+async fn import_curl(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Json(arg): Json<CurlImport>,
+) -> Result<Request> {
+    let mut request = service::curl::parse(&arg.command)
+        .map_err(|e| Error::BadRequest(format!("invalid curl command: {}", e)))?;
+    if let Some(name) = arg.name {
+        request.name = name;
+    }
+    service::import::save_requests(workspace_id, vec![request])
+        .await
+        .map_err(|e| Error::CreateFailed(e.to_string()))?
+        .pop()
+        .ok_or_else(|| Error::NotFound)
+}
+
+#[derive(Debug, Deserialize)]
+struct CurlExportQuery {
+    // the environment whose variables the request is rendered with
+    environment_id: Option<Uuid>,
+}
+
+// the request as it would be sent, with the variables and generators rendered.
+async fn export_curl(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Path(EntityPath { id }): Path<EntityPath>,
+    Query(query): Query<CurlExportQuery>,
+) -> Result<String> {
+    let request = store()
+        .by_id::<Request>(id.into())
+        .await?
+        .filter(|request| request.workspace_id == workspace_id)
+        .ok_or_else(|| Error::NotFound)?;
+    let variables = service::execution::variables(workspace_id, query.environment_id.map(Id::from))
+        .await
+        .map_err(|e| Error::BadRequest(e.to_string()))?;
+    let request = service::execution::prepare_request(&request, &variables)
+        .map_err(|e| Error::BadRequest(format!("invalid request: {}", e)))?;
+    service::curl::render(&request).map_err(|e| Error::BadRequest(e.to_string()))
+}
+
+// one request per entry of the archive, an archive with an invalid entry
+// imports nothing.
+async fn import_har(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Json(har): Json<Har>,
+) -> Result<FetchPaged<Request>> {
+    let requests =
+        service::har::import(har).map_err(|e| Error::BadRequest(format!("invalid HAR: {}", e)))?;
+    let requests = service::import::save_requests(workspace_id, requests)
+        .await
+        .map_err(|e| Error::CreateFailed(e.to_string()))?;
+    Ok((requests.len() as i64, requests).into())
+}
+
+// the requests of a Postman v2.1 collection, and its variables as an
+// environment.
+async fn import_postman(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Json(collection): Json<serde_json::Value>,
+) -> Result<Imported> {
+    let imported = service::postman::import(&collection)
+        .map_err(|e| Error::BadRequest(format!("invalid Postman collection: {}", e)))?;
+    service::import::save(workspace_id, imported)
+        .await
+        .map_err(|e| Error::CreateFailed(e.to_string()))
+}
+
+// one request per operation of an OpenAPI 3 document in JSON or YAML, and the
+// variables of its server as an environment.
+async fn import_openapi(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    document: String,
+) -> Result<Imported> {
+    let imported = service::openapi::import(&document)
+        .map_err(|e| Error::BadRequest(format!("invalid OpenAPI document: {}", e)))?;
+    service::import::save(workspace_id, imported)
+        .await
+        .map_err(|e| Error::CreateFailed(e.to_string()))
+}
@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Path, Query},
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use sqlx_crud::Schema;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        resp::{ExpectRowsAffected, FetchPaged, RowsAffected},
+        Result,
+    },
+    delete,
+    entity::bin::TrappedRequest,
+    retrieve, retrieve_list,
+    storage::Storage,
+};
+
+use super::QueryWith;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct TrappedRequestQuery {
+    pub(crate) bin_id: Option<Uuid>,
+    pub(crate) method: Option<String>,
+    pub(crate) page: Option<usize>,
+    pub(crate) per_page: Option<usize>,
+}
+
+impl QueryWith<TrappedRequest> for TrappedRequestQuery {
+    fn query_with(self, query: &mut crate::storage::Filter) {
+        if let Some(bin_id) = self.bin_id {
+            query.and_eq("bin_id", bin_id);
+        }
+        if let Some(ref method) = self.method {
+            query.and_eq("method", method.to_uppercase());
+        }
+        query.order_desc("trapped_at");
+    }
+}
+
+// trapped requests are written by `/trap` only, so there is no create or update.
+pub(crate) fn router() -> Router {
+    Router::new()
+        .route("/", get(retrieve_list))
+        .route("/:id", get(retrieve).delete(delete))
+}
+
+retrieve!(TrappedRequest);
+retrieve_list!(TrappedRequestQuery, TrappedRequest);
+delete!(TrappedRequest);
@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Path, Query},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use sqlx_crud::Schema;
+
+use crate::{
+    api::{
+        error::Error,
+        resp::{ExpectRowsAffected, FetchPaged, RowsAffected},
+        Result,
+    },
+    delete,
+    entity::variable::Variable,
+    retrieve, retrieve_list, router, service,
+    storage::Storage,
+};
+
+use super::{workspace::CurrentWorkspace, EntityPath, QueryWith};
+
+#[derive(Debug, Deserialize)]
+struct VariableRequest {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct VariableQuery {
+    pub(crate) name: Option<String>,
+    pub(crate) page: Option<usize>,
+    pub(crate) per_page: Option<usize>,
+}
+
+impl QueryWith<Variable> for VariableQuery {
+    fn query_with(self, query: &mut crate::storage::Filter) {
+        if let Some(ref name) = self.name {
+            query.and_contains("name", name);
+        }
+        query.order_asc("name");
+    }
+}
+
+router!();
+retrieve!(Variable, scoped);
+retrieve_list!(VariableQuery, Variable, scoped);
+delete!(Variable, scoped);
+
+// a workspace has one variable of a name, creating a variable which exists
+// sets its value.
+async fn create(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Json(arg): Json<VariableRequest>,
+) -> Result<Variable> {
+    service::variable::set(workspace_id, arg.name, arg.value, None)
+        .await
+        .map_err(|e| Error::CreateFailed(e.to_string()))
+}
+
+// a variable can not take the name of another one of the workspace.
+async fn update(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Path(EntityPath { id }): Path<EntityPath>,
+    Json(arg): Json<VariableRequest>,
+) -> Result<RowsAffected> {
+    service::variable::update(workspace_id, id.into(), arg.name, arg.value).await?;
+    Ok(1.into())
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRequestParts, Path, Query},
+    http::request::Parts,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use sqlx_crud::Schema;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        error::Error,
+        resp::{ExpectRowsAffected, FetchPaged, RowsAffected},
+        Result,
+    },
+    create,
+    entity::{
+        id::Id,
+        workspace::{Workspace, DEFAULT_WORKSPACE},
+    },
+    retrieve, retrieve_list, service,
+    storage::{store, Storage},
+    update,
+};
+
+use super::{QueryWith, UpdateWith};
+
+const WORKSPACE_ID: &str = "workspace_id";
+const X_WORKSPACE_ID: &str = "x-workspace-id";
+
+// the workspace a request works in, taken from the `workspace_id` path
+// parameter or the `x-workspace-id` header, in that order. requests without
+// either work in the default workspace.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CurrentWorkspace(pub(crate) Id);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CurrentWorkspace
+where
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self> {
+        let from_path = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+            .await
+            .ok()
+            .and_then(|Path(params)| params.get(WORKSPACE_ID).cloned());
+        let from_header = || {
+            parts
+                .headers
+                .get(X_WORKSPACE_ID)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let id = match from_path.or_else(from_header) {
+            Some(id) => Uuid::parse_str(&id)
+                .map_err(|e| Error::BadRequest(format!("invalid workspace id: {}", e)))?
+                .into(),
+            None => return Ok(Self(DEFAULT_WORKSPACE)),
+        };
+        store()
+            .by_id::<Workspace>(id)
+            .await?
+            .map(|workspace| Self(workspace.id))
+            .ok_or_else(|| Error::NotFound)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkspaceRequest {
+    name: String,
+    description: Option<String>,
+}
+
+impl From<WorkspaceRequest> for Workspace {
+    fn from(request: WorkspaceRequest) -> Workspace {
+        Workspace {
+            id: Id::new(),
+            name: request.name,
+            description: request.description,
+            ..Default::default()
+        }
+    }
+}
+
+impl UpdateWith<WorkspaceRequest> for Workspace {
+    fn update_with(mut self, request: WorkspaceRequest) -> Workspace {
+        self.name = request.name;
+        self.description = request.description;
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct WorkspaceQuery {
+    pub(crate) name: Option<String>,
+    pub(crate) page: Option<usize>,
+    pub(crate) per_page: Option<usize>,
+}
+
+impl QueryWith<Workspace> for WorkspaceQuery {
+    fn query_with(self, query: &mut crate::storage::Filter) {
+        if let Some(ref name) = self.name {
+            query.and_contains("name", name);
+        }
+    }
+}
+
+// the workspace id is named `workspace_id` rather than `id` so that the routes
+// agree with the nested `/workspace/:workspace_id/...` resources.
+pub(crate) fn router() -> Router {
+    Router::new()
+        .route("/", get(retrieve_list).post(create))
+        .route("/:workspace_id", get(retrieve).put(update).delete(delete))
+        .route("/:workspace_id/clone", post(clone))
+}
+
+create!(WorkspaceRequest, Workspace);
+retrieve!(Workspace);
+retrieve_list!(WorkspaceQuery, Workspace);
+update!(WorkspaceRequest, Workspace);
+
+#[derive(Debug, Deserialize)]
+struct CloneRequest {
+    name: String,
+    description: Option<String>,
+}
+
+async fn clone(
+    CurrentWorkspace(workspace_id): CurrentWorkspace,
+    Json(arg): Json<CloneRequest>,
+) -> Result<Workspace> {
+    service::workspace::clone_workspace(workspace_id, arg.name, arg.description)
+        .await
+        .map_err(|e| Error::CreateFailed(e.to_string()))
+}
+
+// deleting a workspace deletes the requests, environments, collections and
+// their runs, variables and executions in it as well.
+async fn delete(CurrentWorkspace(workspace_id): CurrentWorkspace) -> Result<RowsAffected> {
+    if workspace_id == DEFAULT_WORKSPACE {
+        return Err(Error::BadRequest(
+            "the default workspace can not be deleted".to_string(),
+        ));
+    }
+    service::workspace::delete_workspace(workspace_id)
+        .await?
+        .expect(1)
+}
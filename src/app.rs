@@ -1,7 +1,18 @@
-use crate::{api, config, db, log};
-use anyhow::Result;
+use crate::{
+    api, config, db,
+    entity::{
+        id::Id,
+        workspace::{Workspace, DEFAULT_WORKSPACE},
+    },
+    log,
+    service::{self, import::Imported},
+    storage::{self, store, Storage},
+};
+use anyhow::{anyhow, Result};
 use axum::Server;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::{collections::HashMap, fs, io, net::SocketAddr};
+use uuid::Uuid;
 
 const fn version() -> &'static str {
     env!("CARGO_PKG_VERSION")
@@ -34,6 +45,149 @@ pub(crate) enum App {
             help = "set a custom config file"
         )]
         config_file: Option<String>,
+        #[clap(
+            long = "ephemeral",
+            help = "keep everything in memory instead of the database"
+        )]
+        ephemeral: bool,
+        #[clap(
+            long = "capacity",
+            value_name = "N",
+            default_value_t = 1000,
+            requires = "ephemeral",
+            help = "the number of executions, collection runs and trapped requests kept when ephemeral"
+        )]
+        capacity: usize,
+    },
+    #[clap(
+        name = "migrate",
+        about = "apply, list or revert the database migrations."
+    )]
+    Migrate {
+        #[clap(
+            long = "config-file",
+            value_name = "FILE",
+            help = "set a custom config file"
+        )]
+        config_file: Option<String>,
+        #[clap(subcommand)]
+        mode: MigrateMode,
+    },
+    #[clap(name = "import", about = "create saved requests from another format.")]
+    Import {
+        #[clap(
+            long = "config-file",
+            value_name = "FILE",
+            help = "set a custom config file"
+        )]
+        config_file: Option<String>,
+        #[clap(
+            long = "workspace",
+            value_name = "ID",
+            help = "the workspace to import into, the default one without it"
+        )]
+        workspace: Option<Uuid>,
+        #[clap(subcommand)]
+        source: ImportSource,
+    },
+    #[clap(name = "export", about = "export the executions to another format.")]
+    Export {
+        #[clap(
+            long = "config-file",
+            value_name = "FILE",
+            help = "set a custom config file"
+        )]
+        config_file: Option<String>,
+        #[clap(
+            long = "workspace",
+            value_name = "ID",
+            help = "the workspace to export from, the default one without it"
+        )]
+        workspace: Option<Uuid>,
+        #[clap(subcommand)]
+        format: ExportFormat,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ImportSource {
+    #[clap(name = "curl", about = "import a curl command line.")]
+    Curl {
+        #[clap(
+            long = "name",
+            value_name = "NAME",
+            help = "the name of the request, its method and path by default"
+        )]
+        name: Option<String>,
+        #[clap(help = "the curl command, read from stdin without it")]
+        command: Option<String>,
+    },
+    #[clap(name = "har", about = "import the entries of an HTTP Archive.")]
+    Har {
+        #[clap(help = "the HAR file, read from stdin without it")]
+        file: Option<String>,
+    },
+    #[clap(
+        name = "postman",
+        about = "import the requests and variables of a Postman v2.1 collection."
+    )]
+    Postman {
+        #[clap(help = "the collection file, read from stdin without it")]
+        file: Option<String>,
+    },
+    #[clap(
+        name = "openapi",
+        about = "import the operations of an OpenAPI 3 document in JSON or YAML."
+    )]
+    OpenApi {
+        #[clap(help = "the document file, read from stdin without it")]
+        file: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ExportFormat {
+    #[clap(
+        name = "har",
+        about = "print the latest executions as an HTTP Archive."
+    )]
+    Har {
+        #[clap(
+            long = "request-id",
+            value_name = "ID",
+            help = "only export the executions of the request"
+        )]
+        request_id: Option<Uuid>,
+        #[clap(
+            long = "limit",
+            value_name = "N",
+            default_value_t = service::har::EXPORT_LIMIT,
+            help = "the number of latest executions exported"
+        )]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum MigrateMode {
+    #[clap(name = "up", about = "apply the pending migrations.")]
+    Up,
+    #[clap(
+        name = "status",
+        about = "list the migrations and whether they are applied."
+    )]
+    Status,
+    #[clap(
+        name = "down",
+        about = "revert the latest migration, or all of them after the target version."
+    )]
+    Down {
+        #[clap(
+            long = "target",
+            value_name = "VERSION",
+            help = "the version to revert to, 0 reverts everything"
+        )]
+        target: Option<i64>,
     },
 }
 
@@ -41,15 +195,162 @@ impl App {
     async fn execute(&self) -> Result<()> {
         match self {
             App::Dump => Self::dump_default_config().await,
-            App::Serve { config_file } => {
+            App::Serve {
+                config_file,
+                ephemeral,
+                capacity,
+            } => {
                 config::init_config(config_file)?;
-                let _ = log::init_log().await?;
-                db::init_database().await?;
+                log::init_log().await?;
+                storage::init_storage(ephemeral.then_some(*capacity)).await?;
                 Self::serve().await
             }
+            App::Migrate { config_file, mode } => {
+                config::init_config(config_file)?;
+                log::init_log().await?;
+                Self::migrate(mode).await
+            }
+            App::Import {
+                config_file,
+                workspace,
+                source,
+            } => {
+                config::init_config(config_file)?;
+                log::init_log().await?;
+                storage::init_storage(None).await?;
+                Self::import(workspace.map(Id::from), source).await
+            }
+            App::Export {
+                config_file,
+                workspace,
+                format,
+            } => {
+                config::init_config(config_file)?;
+                log::init_log().await?;
+                storage::init_storage(None).await?;
+                Self::export(workspace.map(Id::from), format).await
+            }
         }
     }
 
+    // the workspace of the id, or the default one without it.
+    async fn workspace(workspace: Option<Id>) -> Result<Id> {
+        Ok(match workspace {
+            Some(id) => {
+                store()
+                    .by_id::<Workspace>(id)
+                    .await?
+                    .ok_or_else(|| anyhow!("workspace {} not found", id))?
+                    .id
+            }
+            None => DEFAULT_WORKSPACE,
+        })
+    }
+
+    // the content of the file, or of stdin without one.
+    fn read_input(file: Option<&String>) -> Result<String> {
+        Ok(match file {
+            Some(file) => fs::read_to_string(file)?,
+            None => io::read_to_string(io::stdin())?,
+        })
+    }
+
+    async fn export(workspace: Option<Id>, format: &ExportFormat) -> Result<()> {
+        let workspace_id = Self::workspace(workspace).await?;
+        match format {
+            ExportFormat::Har { request_id, limit } => {
+                let har =
+                    service::har::export_latest(workspace_id, request_id.map(Id::from), *limit)
+                        .await?;
+                println!("{}", serde_json::to_string_pretty(&har)?);
+            }
+        }
+        Ok(())
+    }
+
+    async fn import(workspace: Option<Id>, source: &ImportSource) -> Result<()> {
+        let workspace_id = Self::workspace(workspace).await?;
+        let imported = match source {
+            ImportSource::Curl { name, command } => {
+                let command = match command {
+                    Some(command) => command.clone(),
+                    None => Self::read_input(None)?,
+                };
+                let mut request = service::curl::parse(&command)?;
+                if let Some(name) = name {
+                    request.name = name.clone();
+                }
+                Imported {
+                    requests: vec![request],
+                    ..Default::default()
+                }
+            }
+            ImportSource::Har { file } => Imported {
+                requests: service::har::import(serde_json::from_str(&Self::read_input(
+                    file.as_ref(),
+                )?)?)?,
+                ..Default::default()
+            },
+            ImportSource::Postman { file } => {
+                service::postman::import(&serde_json::from_str(&Self::read_input(file.as_ref())?)?)?
+            }
+            ImportSource::OpenApi { file } => {
+                service::openapi::import(&Self::read_input(file.as_ref())?)?
+            }
+        };
+        let imported = service::import::save(workspace_id, imported).await?;
+        println!("{}", serde_json::to_string_pretty(&imported)?);
+        Ok(())
+    }
+
+    async fn migrate(mode: &MigrateMode) -> Result<()> {
+        let pool = db::connect().await?;
+        match mode {
+            MigrateMode::Up => pool.migrate().await?,
+            MigrateMode::Status => {
+                let applied = pool
+                    .applied_migrations()
+                    .await?
+                    .into_iter()
+                    .map(|m| (m.version, m.checksum))
+                    .collect::<HashMap<_, _>>();
+                for migration in pool.migrator().iter() {
+                    if migration.migration_type.is_down_migration() {
+                        continue;
+                    }
+                    let status = match applied.get(&migration.version) {
+                        Some(checksum) if *checksum == migration.checksum => "applied",
+                        Some(_) => "modified",
+                        None => "pending",
+                    };
+                    println!(
+                        "{:<16}{:<10}{}",
+                        migration.version, status, migration.description
+                    );
+                }
+            }
+            MigrateMode::Down { target } => {
+                let target = match target {
+                    Some(target) => *target,
+                    // the version before the latest applied one
+                    None => {
+                        let mut versions = pool
+                            .applied_migrations()
+                            .await?
+                            .into_iter()
+                            .map(|m| m.version)
+                            .collect::<Vec<_>>();
+                        versions.sort_unstable();
+                        versions.pop();
+                        versions.pop().unwrap_or(0)
+                    }
+                };
+                pool.revert(target).await?;
+            }
+        }
+        Ok(())
+    }
+
     async fn dump_default_config() -> Result<()> {
         let conf = config::Config::default();
         let data = toml::to_string_pretty(&conf)?;
@@ -62,7 +363,9 @@ impl App {
         let addr = format!("{}:{}", base.host, base.port).parse()?;
         let app = api::router();
         tracing::info!("listening on {}", addr);
-        Server::bind(&addr).serve(app.into_make_service()).await?;
+        Server::bind(&addr)
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+            .await?;
         Ok(())
     }
 }
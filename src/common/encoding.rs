@@ -0,0 +1,8 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+pub(crate) fn serialize_base64<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&STANDARD.encode(bytes))
+}
@@ -9,24 +9,70 @@ macro_rules! router {
     };
 }
 
+// the `scoped` variants of the macros below work on entities that belong to a
+// workspace, the workspace is taken from `CurrentWorkspace` and entities of
+// other workspaces are treated as not found.
+//
+// creating, updating and deleting an entity publishes an event, see
+// `service::event`.
+
 #[macro_export]
 macro_rules! create {
     ($type_arg:ty, $type_entity:ty) => {
         async fn create(Json(arg): Json<$type_arg>) -> Result<$type_entity> {
             let entity: $type_entity = arg.into();
             let id = entity.id.clone();
-            entity
-                .create(db::db_pool())
+            $crate::storage::store()
+                .create(entity)
                 .await
                 .map_err(|e| {
                     tracing::error!("created failed: {}", e);
-                    crate::api::error::Error::CreateFailed(e.to_string())
+                    $crate::api::error::Error::CreateFailed(e.to_string())
                 })?
-                .rows_affected()
                 .expect(1)?;
-            <$type_entity>::by_id(db::db_pool(), id)
+            let entity = $crate::storage::store()
+                .by_id::<$type_entity>(id)
                 .await?
-                .ok_or_else(|| crate::api::error::Error::NotFound)
+                .ok_or_else(|| $crate::api::error::Error::NotFound)?;
+            $crate::service::event::Event::new(
+                <$type_entity>::table_name(),
+                $crate::service::event::Action::Created,
+                entity.id.to_string(),
+                &entity,
+            )
+            .publish();
+            Ok(entity)
+        }
+    };
+    ($type_arg:ty, $type_entity:ty, scoped) => {
+        async fn create(
+            CurrentWorkspace(workspace_id): CurrentWorkspace,
+            Json(arg): Json<$type_arg>,
+        ) -> Result<$type_entity> {
+            let mut entity: $type_entity = arg.into();
+            entity.workspace_id = workspace_id;
+            let id = entity.id.clone();
+            $crate::storage::store()
+                .create(entity)
+                .await
+                .map_err(|e| {
+                    tracing::error!("created failed: {}", e);
+                    $crate::api::error::Error::CreateFailed(e.to_string())
+                })?
+                .expect(1)?;
+            let entity = $crate::storage::store()
+                .by_id::<$type_entity>(id)
+                .await?
+                .ok_or_else(|| $crate::api::error::Error::NotFound)?;
+            $crate::service::event::Event::new(
+                <$type_entity>::table_name(),
+                $crate::service::event::Action::Created,
+                entity.id.to_string(),
+                &entity,
+            )
+            .in_workspace(workspace_id)
+            .publish();
+            Ok(entity)
         }
     };
 }
@@ -36,9 +82,23 @@ macro_rules! retrieve {
     ($type:ty) => {
         async fn retrieve(Path(id): Path<Uuid>) -> Result<$type> {
             tracing::info!("retrieving");
-            <$type>::by_id(db::db_pool(), id.hyphenated())
+            $crate::storage::store()
+                .by_id::<$type>(id.into())
+                .await?
+                .ok_or_else(|| $crate::api::error::Error::NotFound)
+        }
+    };
+    ($type:ty, scoped) => {
+        async fn retrieve(
+            CurrentWorkspace(workspace_id): CurrentWorkspace,
+            Path(EntityPath { id }): Path<EntityPath>,
+        ) -> Result<$type> {
+            tracing::info!("retrieving");
+            $crate::storage::store()
+                .by_id::<$type>(id.into())
                 .await?
-                .ok_or_else(|| crate::api::error::Error::NotFound)
+                .filter(|entity| entity.workspace_id == workspace_id)
+                .ok_or_else(|| $crate::api::error::Error::NotFound)
         }
     };
 }
@@ -52,17 +112,35 @@ macro_rules! retrieve_list {
             let page = query.page.map(|i| if i == 0 { 1 } else { i }).unwrap_or(1);
             let per_page = query.per_page.unwrap_or(10);
             let offset = (page - 1) * per_page;
-            let mut builder = sql_builder::SqlBuilder::select_from(<$type_entity>::table_name());
-            query.query_with(&mut builder);
-            let count_sql = builder.clone().count("0").sql().unwrap();
-            let count: i64 = sqlx::query(&count_sql)
-                .fetch_one(db::db_pool())
-                .await?
-                .get(0);
-            builder.offset(offset).limit(per_page);
-            let data_sql = builder.sql().unwrap();
-            let list = sqlx::query_as::<_, $type_entity>(&data_sql)
-                .fetch_all(db::db_pool())
+            let mut filter = $crate::storage::Filter::default();
+            query.query_with(&mut filter);
+            let count = $crate::storage::store()
+                .count::<$type_entity>(&filter)
+                .await?;
+            filter.page(offset, per_page);
+            let list = $crate::storage::store()
+                .list::<$type_entity>(&filter)
+                .await?;
+            Ok((count, list).into())
+        }
+    };
+    ($type_query:ty, $type_entity:ty, scoped) => {
+        async fn retrieve_list(
+            CurrentWorkspace(workspace_id): CurrentWorkspace,
+            Query(query): Query<$type_query>,
+        ) -> Result<FetchPaged<$type_entity>> {
+            let page = query.page.map(|i| if i == 0 { 1 } else { i }).unwrap_or(1);
+            let per_page = query.per_page.unwrap_or(10);
+            let offset = (page - 1) * per_page;
+            let mut filter = $crate::storage::Filter::default();
+            filter.and_eq("workspace_id", workspace_id);
+            query.query_with(&mut filter);
+            let count = $crate::storage::store()
+                .count::<$type_entity>(&filter)
+                .await?;
+            filter.page(offset, per_page);
+            let list = $crate::storage::store()
+                .list::<$type_entity>(&filter)
                 .await?;
             Ok((count, list).into())
         }
@@ -76,14 +154,50 @@ macro_rules! update {
             Path(id): Path<Uuid>,
             Json(request): Json<$type_arg>,
         ) -> Result<RowsAffected> {
-            <$type_entity>::by_id(db::db_pool(), id.hyphenated())
+            let entity = $crate::storage::store()
+                .by_id::<$type_entity>(id.into())
                 .await?
-                .ok_or_else(|| crate::api::error::Error::NotFound)?
-                .update_with(request)
-                .update(db::db_pool())
+                .ok_or_else(|| $crate::api::error::Error::NotFound)?
+                .update_with(request);
+            let rows_affected = $crate::storage::store()
+                .update(entity.clone())
                 .await?
-                .rows_affected()
-                .expect(1)
+                .expect(1)?;
+            $crate::service::event::Event::new(
+                <$type_entity>::table_name(),
+                $crate::service::event::Action::Updated,
+                entity.id.to_string(),
+                &entity,
+            )
+            .publish();
+            Ok(rows_affected)
+        }
+    };
+    ($type_arg:ty, $type_entity:ty, scoped) => {
+        async fn update(
+            CurrentWorkspace(workspace_id): CurrentWorkspace,
+            Path(EntityPath { id }): Path<EntityPath>,
+            Json(request): Json<$type_arg>,
+        ) -> Result<RowsAffected> {
+            let entity = $crate::storage::store()
+                .by_id::<$type_entity>(id.into())
+                .await?
+                .filter(|entity| entity.workspace_id == workspace_id)
+                .ok_or_else(|| $crate::api::error::Error::NotFound)?
+                .update_with(request);
+            let rows_affected = $crate::storage::store()
+                .update(entity.clone())
+                .await?
+                .expect(1)?;
+            $crate::service::event::Event::new(
+                <$type_entity>::table_name(),
+                $crate::service::event::Action::Updated,
+                entity.id.to_string(),
+                &entity,
+            )
+            .in_workspace(workspace_id)
+            .publish();
+            Ok(rows_affected)
         }
     };
 }
@@ -92,13 +206,47 @@ macro_rules! update {
 macro_rules! delete {
     ($type:ty) => {
         async fn delete(Path(id): Path<Uuid>) -> Result<RowsAffected> {
-            <$type>::by_id(db::db_pool(), id.hyphenated())
+            let entity = $crate::storage::store()
+                .by_id::<$type>(id.into())
+                .await?
+                .ok_or_else(|| $crate::api::error::Error::NotFound)?;
+            let rows_affected = $crate::storage::store()
+                .delete(entity.clone())
+                .await?
+                .expect(1)?;
+            $crate::service::event::Event::new(
+                <$type>::table_name(),
+                $crate::service::event::Action::Deleted,
+                entity.id.to_string(),
+                &entity,
+            )
+            .publish();
+            Ok(rows_affected)
+        }
+    };
+    ($type:ty, scoped) => {
+        async fn delete(
+            CurrentWorkspace(workspace_id): CurrentWorkspace,
+            Path(EntityPath { id }): Path<EntityPath>,
+        ) -> Result<RowsAffected> {
+            let entity = $crate::storage::store()
+                .by_id::<$type>(id.into())
                 .await?
-                .ok_or_else(|| crate::api::error::Error::NotFound)?
-                .delete(db::db_pool())
+                .filter(|entity| entity.workspace_id == workspace_id)
+                .ok_or_else(|| $crate::api::error::Error::NotFound)?;
+            let rows_affected = $crate::storage::store()
+                .delete(entity.clone())
                 .await?
-                .rows_affected()
-                .expect(1)
+                .expect(1)?;
+            $crate::service::event::Event::new(
+                <$type>::table_name(),
+                $crate::service::event::Action::Deleted,
+                entity.id.to_string(),
+                &entity,
+            )
+            .in_workspace(workspace_id)
+            .publish();
+            Ok(rows_affected)
         }
     };
 }
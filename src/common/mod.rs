@@ -1,2 +1,3 @@
 #[macro_use]
 pub(crate) mod macros;
+pub(crate) mod encoding;
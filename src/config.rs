@@ -4,6 +4,8 @@ use std::fs;
 use once_cell::sync::OnceCell;
 use serde::{de::Error, Deserialize, Serialize};
 
+use crate::entity::client::ClientOptions;
+
 static GLOBAL_CONFIG: OnceCell<Config> = OnceCell::new();
 
 pub(crate) fn global_config() -> &'static Config {
@@ -35,6 +37,9 @@ pub(crate) struct Config {
     pub(crate) base: BaseConfig,
     pub(crate) log: LogConfig,
     pub(crate) db: DbConfig,
+    // the options of the requests which do not set them
+    #[serde(default)]
+    pub(crate) client: ClientOptions,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -91,30 +96,118 @@ impl Default for LogConfig {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum DbDriver {
+    #[default]
+    MySql,
+    Sqlite,
+    Postgres,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct DbConfig {
-    pub(crate) sockaddr: String,
-    pub(crate) database: String,
-    pub(crate) user: String,
-    pub(crate) password: String,
+    #[serde(default)]
+    pub(crate) driver: DbDriver,
+    // required by mysql and postgres
+    #[serde(default)]
+    pub(crate) sockaddr: Option<String>,
+    #[serde(default)]
+    pub(crate) database: Option<String>,
+    #[serde(default)]
+    pub(crate) user: Option<String>,
+    #[serde(default)]
+    pub(crate) password: Option<String>,
+    // used by sqlite, created when it does not exist
+    #[serde(default = "default_db_file")]
+    pub(crate) file: String,
+    // apply the pending migrations when the server starts
+    #[serde(default = "default_auto_migrate")]
+    pub(crate) auto_migrate: bool,
+}
+
+fn default_db_file() -> String {
+    format!("{}.sqlite", env!("CARGO_PKG_NAME"))
+}
+
+fn default_auto_migrate() -> bool {
+    true
 }
 
 impl DbConfig {
-    pub(crate) fn db_url(&self) -> String {
-        format!(
-            "mysql://{}:{}@{}/{}?charset=utf8mb4&collation=utf8mb4_unicode_ci",
-            self.user, self.password, self.sockaddr, self.database
-        )
+    pub(crate) fn db_url(&self) -> Result<String> {
+        Ok(match self.driver {
+            DbDriver::MySql => {
+                let (sockaddr, database, user, password) = self.server()?;
+                format!(
+                    "mysql://{}:{}@{}/{}?charset=utf8mb4&collation=utf8mb4_unicode_ci",
+                    user, password, sockaddr, database
+                )
+            }
+            DbDriver::Sqlite => format!("sqlite://{}?mode=rwc", self.file),
+            DbDriver::Postgres => {
+                let (sockaddr, database, user, password) = self.server()?;
+                format!("postgres://{}:{}@{}/{}", user, password, sockaddr, database)
+            }
+        })
+    }
+
+    // the address, database, user and password of the database server, which
+    // mysql and postgres require.
+    fn server(&self) -> Result<(&str, &str, &str, &str)> {
+        Ok((
+            self.required("sockaddr", &self.sockaddr)?,
+            self.required("database", &self.database)?,
+            self.required("user", &self.user)?,
+            self.required("password", &self.password)?,
+        ))
+    }
+
+    fn required<'a>(&self, name: &str, value: &'a Option<String>) -> Result<&'a str> {
+        value.as_deref().ok_or_else(|| {
+            anyhow::anyhow!("db.{} is required by the {:?} driver", name, self.driver)
+        })
     }
 }
 
 impl Default for DbConfig {
     fn default() -> Self {
         Self {
-            sockaddr: "127.0.0.1:3306".to_string(),
-            database: env!("CARGO_PKG_NAME").to_string(),
-            user: env!("CARGO_PKG_NAME").to_string(),
-            password: "<Password>".to_string(),
+            driver: DbDriver::default(),
+            sockaddr: Some("127.0.0.1:3306".to_string()),
+            database: Some(env!("CARGO_PKG_NAME").to_string()),
+            user: Some(env!("CARGO_PKG_NAME").to_string()),
+            password: Some("<Password>".to_string()),
+            file: default_db_file(),
+            auto_migrate: default_auto_migrate(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqlite_needs_no_server() {
+        let config: DbConfig = toml::from_str("driver = \"sqlite\"\nfile = \"a.sqlite\"").unwrap();
+        assert_eq!(config.db_url().unwrap(), "sqlite://a.sqlite?mode=rwc");
+    }
+
+    #[test]
+    fn server_settings_required_by_mysql_and_postgres() {
+        let config: DbConfig =
+            toml::from_str("driver = \"postgres\"\nsockaddr = \"db:5432\"\ndatabase = \"d\"")
+                .unwrap();
+        let e = config.db_url().unwrap_err();
+        assert!(e.to_string().contains("db.user"), "{}", e);
+        let config: DbConfig = toml::from_str(
+            "sockaddr = \"db:3306\"\ndatabase = \"d\"\nuser = \"u\"\npassword = \"p\"",
+        )
+        .unwrap();
+        assert_eq!(
+            config.db_url().unwrap(),
+            "mysql://u:p@db:3306/d?charset=utf8mb4&collation=utf8mb4_unicode_ci"
+        );
+    }
+}
@@ -1,18 +1,178 @@
-use crate::config::global_config;
-use anyhow;
-use once_cell::sync::OnceCell;
-use sqlx::mysql::MySqlPoolOptions;
+use std::borrow::Cow;
 
-static DB_POOL: OnceCell<sqlx::MySqlPool> = OnceCell::new();
+use crate::config::{global_config, DbDriver};
+use once_cell::sync::Lazy;
+use sqlx::{
+    migrate::{AppliedMigration, Migrate, Migration, Migrator},
+    mysql::MySqlPoolOptions,
+    postgres::PgPoolOptions,
+    sqlite::SqlitePoolOptions,
+    Executor,
+};
 
-pub(crate) async fn init_database() -> anyhow::Result<()> {
+// the migrations of each driver are embedded in the binary.
+static MYSQL_MIGRATOR: Lazy<Migrator> =
+    Lazy::new(|| without_empty(sqlx::migrate!("./migrations/mysql")));
+static SQLITE_MIGRATOR: Lazy<Migrator> =
+    Lazy::new(|| without_empty(sqlx::migrate!("./migrations/sqlite")));
+static POSTGRES_MIGRATOR: Lazy<Migrator> =
+    Lazy::new(|| without_empty(sqlx::migrate!("./migrations/postgres")));
+
+// what a script without statements runs instead.
+const NOTHING: &str = "SELECT 1";
+
+// the tables the published mysql migrations expect but never create, see the
+// script for why.
+const MYSQL_BASELINE: &str = include_str!("../migrations/mysql_baseline.sql");
+
+// the pool of the database driver selected in the configuration, it is the
+// storage used unless the server runs ephemeral, see `storage::database`.
+#[derive(Debug)]
+pub(crate) enum DbPool {
+    MySql(sqlx::MySqlPool),
+    Sqlite(sqlx::SqlitePool),
+    Postgres(sqlx::PgPool),
+}
+
+pub(crate) async fn connect() -> anyhow::Result<DbPool> {
     let config = global_config();
-    let pool = MySqlPoolOptions::new().connect(&config.db.db_url()).await?;
-    DB_POOL.set(pool).expect("set global database pool failed.");
-    tracing::info!("database connected.");
-    Ok(())
+    let url = config.db.db_url()?;
+    let pool = match config.db.driver {
+        DbDriver::MySql => DbPool::MySql(MySqlPoolOptions::new().connect(&url).await?),
+        DbDriver::Sqlite => DbPool::Sqlite(SqlitePoolOptions::new().connect(&url).await?),
+        DbDriver::Postgres => DbPool::Postgres(PgPoolOptions::new().connect(&url).await?),
+    };
+    tracing::info!("database connected with driver {:?}.", config.db.driver);
+    Ok(pool)
 }
 
-pub(crate) fn db_pool() -> &'static sqlx::MySqlPool {
-    DB_POOL.get().expect("get global database pool failed.")
+// mysql refuses a script without statements, such as the baseline executions
+// migration which is only a comment. such a script runs a statement doing
+// nothing instead, it keeps its checksum so an applied one still matches.
+fn without_empty(migrator: Migrator) -> Migrator {
+    let migrations = migrator
+        .migrations
+        .iter()
+        .map(|migration| {
+            if is_empty(&migration.sql) {
+                Migration {
+                    sql: Cow::Borrowed(NOTHING),
+                    ..migration.clone()
+                }
+            } else {
+                migration.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+    Migrator {
+        migrations: Cow::Owned(migrations),
+        ..migrator
+    }
+}
+
+// whether the script has nothing but comments and blanks.
+fn is_empty(sql: &str) -> bool {
+    sql.lines()
+        .map(str::trim)
+        .all(|line| line.is_empty() || line.starts_with("--"))
+}
+
+impl DbPool {
+    pub(crate) fn migrator(&self) -> &'static Migrator {
+        match self {
+            DbPool::MySql(_) => &MYSQL_MIGRATOR,
+            DbPool::Sqlite(_) => &SQLITE_MIGRATOR,
+            DbPool::Postgres(_) => &POSTGRES_MIGRATOR,
+        }
+    }
+
+    // apply the migrations which are not applied yet.
+    pub(crate) async fn migrate(&self) -> anyhow::Result<()> {
+        let migrator = self.migrator();
+        match self {
+            DbPool::MySql(pool) => {
+                pool.execute(MYSQL_BASELINE).await?;
+                migrator.run(pool).await?
+            }
+            DbPool::Sqlite(pool) => migrator.run(pool).await?,
+            DbPool::Postgres(pool) => migrator.run(pool).await?,
+        }
+        tracing::info!("database migrated.");
+        Ok(())
+    }
+
+    // revert the applied migrations newer than the target version.
+    pub(crate) async fn revert(&self, target: i64) -> anyhow::Result<()> {
+        let migrator = self.migrator();
+        match self {
+            DbPool::MySql(pool) => migrator.undo(pool, target).await?,
+            DbPool::Sqlite(pool) => migrator.undo(pool, target).await?,
+            DbPool::Postgres(pool) => migrator.undo(pool, target).await?,
+        }
+        tracing::info!("database reverted to version {}.", target);
+        Ok(())
+    }
+
+    pub(crate) async fn applied_migrations(&self) -> anyhow::Result<Vec<AppliedMigration>> {
+        Ok(match self {
+            DbPool::MySql(pool) => list_applied(&mut *pool.acquire().await?).await?,
+            DbPool::Sqlite(pool) => list_applied(&mut *pool.acquire().await?).await?,
+            DbPool::Postgres(pool) => list_applied(&mut *pool.acquire().await?).await?,
+        })
+    }
+}
+
+async fn list_applied<C: Migrate>(conn: &mut C) -> anyhow::Result<Vec<AppliedMigration>> {
+    conn.ensure_migrations_table().await?;
+    Ok(conn.list_applied_migrations().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comment_only_scripts_are_empty() {
+        assert!(is_empty("-- Add migration script here\n"));
+        assert!(is_empty("\n  -- a comment\n\n"));
+        assert!(!is_empty("-- a comment\nDROP TABLE requests;"));
+    }
+
+    #[test]
+    fn empty_scripts_run_nothing_and_keep_their_checksum() {
+        let embedded = sqlx::migrate!("./migrations/mysql");
+        let migrator = &*MYSQL_MIGRATOR;
+        assert_eq!(migrator.migrations.len(), embedded.migrations.len());
+        let mut replaced = 0;
+        for (migration, original) in migrator.iter().zip(embedded.iter()) {
+            assert_eq!(migration.version, original.version);
+            assert_eq!(migration.checksum, original.checksum);
+            if is_empty(&original.sql) {
+                assert_eq!(migration.sql, NOTHING);
+                replaced += 1;
+            } else {
+                assert_eq!(migration.sql, original.sql);
+            }
+        }
+        // the up and down scripts of the baseline executions migration
+        assert_eq!(replaced, 2);
+    }
+
+    #[tokio::test]
+    async fn sqlite_migrates_up_and_down() {
+        // a database in memory lives as long as its one connection
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let migrator = &*SQLITE_MIGRATOR;
+        migrator.run(&pool).await.unwrap();
+        sqlx::query("SELECT cookie_jar_id, finished_at FROM collection_runs")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        migrator.undo(&pool, 0).await.unwrap();
+        migrator.run(&pool).await.unwrap();
+    }
 }
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// a check of the response of an execution, saved with the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum Assertion {
+    StatusEquals { status: u16 },
+    // both bounds are inclusive
+    StatusInRange { min: u16, max: u16 },
+    HeaderPresent { name: String },
+    HeaderMatches { name: String, pattern: String },
+    JsonPathEquals { path: String, value: Value },
+    // a string containing the text, an array containing the element or an
+    // object containing the fields of the value
+    JsonPathContains { path: String, value: Value },
+    BodyMatches { pattern: String },
+    LatencyBelow { ms: i64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AssertionResult {
+    #[serde(flatten)]
+    pub(crate) assertion: Assertion,
+    pub(crate) passed: bool,
+    // what was found in the response
+    pub(crate) actual: Value,
+    // why the assertion could not be evaluated, e.g. an invalid pattern
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
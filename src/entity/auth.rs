@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+// how a request authenticates, saved with the request. the fields are
+// rendered with the variables of the execution like the rest of the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum Auth {
+    Basic {
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    // sent as the header or the query parameter `name`
+    ApiKey {
+        name: String,
+        value: String,
+        #[serde(default, rename = "in")]
+        location: ApiKeyLocation,
+    },
+    // answers the challenge of a 401 response
+    Digest {
+        username: String,
+        password: String,
+    },
+    AwsSigV4 {
+        access_key_id: String,
+        secret_access_key: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        session_token: Option<String>,
+        region: String,
+        service: String,
+    },
+    // the token is fetched from `token_url` and reused until it expires
+    #[serde(rename = "oauth2_client_credentials")]
+    OAuth2ClientCredentials {
+        token_url: String,
+        client_id: String,
+        client_secret: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scope: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ApiKeyLocation {
+    #[default]
+    Header,
+    Query,
+}
@@ -0,0 +1,47 @@
+use crate::api::resp::FetchOne;
+use crate::entity::id::Id;
+use axum::response::IntoResponse;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::FromRow;
+use sqlx_crud::{add_timed_fields, SqlxCrud};
+
+// a bin collects whatever is sent to `/trap/<bin id>/...` and answers every
+// trapped request with the configured status, headers and body.
+#[add_timed_fields]
+#[derive(Debug, Clone, Serialize, FromRow, SqlxCrud, Default)]
+pub(crate) struct Bin {
+    pub(crate) id: Id,
+    pub(crate) name: String,
+    pub(crate) status_code: i32,
+    pub(crate) headers: Value,
+    pub(crate) body: Option<String>,
+}
+
+impl IntoResponse for Bin {
+    fn into_response(self) -> axum::response::Response {
+        FetchOne::new(self).into_response()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, SqlxCrud, Default)]
+pub(crate) struct TrappedRequest {
+    pub(crate) id: Id,
+    pub(crate) bin_id: Id,
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) query: Option<String>,
+    pub(crate) version: String,
+    pub(crate) headers: Value,
+    #[serde(serialize_with = "crate::common::encoding::serialize_base64")]
+    pub(crate) body: Vec<u8>,
+    pub(crate) remote_addr: String,
+    pub(crate) trapped_at: DateTime<Local>,
+}
+
+impl IntoResponse for TrappedRequest {
+    fn into_response(self) -> axum::response::Response {
+        FetchOne::new(self).into_response()
+    }
+}
@@ -0,0 +1,125 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    database::{HasArguments, HasValueRef},
+    encode::IsNull,
+    error::BoxDynError,
+    mysql::MySql,
+    postgres::Postgres,
+    sqlite::Sqlite,
+    Decode, Encode, Type,
+};
+
+// how the body of a request is kept and sent.
+//
+// - `json`: any JSON value, sent as JSON
+// - `text`: a string sent as it is, e.g. XML or SOAP
+// - `form`: an object of the fields, or the encoded string, sent
+//   url-encoded
+// - `multipart`: an array of `Part`s sent as multipart/form-data
+// - `binary`: the base64 of the bytes sent
+//
+// the kind is stored as its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BodyKind {
+    #[default]
+    Json,
+    Text,
+    Form,
+    Multipart,
+    Binary,
+}
+
+impl BodyKind {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Text => "text",
+            Self::Form => "form",
+            Self::Multipart => "multipart",
+            Self::Binary => "binary",
+        }
+    }
+
+    // the content type sent when the request does not set one, multipart gets
+    // a boundary as well, see `body::sent_type`.
+    pub(crate) fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Text => "text/plain; charset=utf-8",
+            Self::Form => "application/x-www-form-urlencoded",
+            Self::Multipart => "multipart/form-data",
+            Self::Binary => "application/octet-stream",
+        }
+    }
+}
+
+impl fmt::Display for BodyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BodyKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "json" => Self::Json,
+            "text" => Self::Text,
+            "form" => Self::Form,
+            "multipart" => Self::Multipart,
+            "binary" => Self::Binary,
+            other => return Err(anyhow!("unknown body kind: {}", other)),
+        })
+    }
+}
+
+// a field of a multipart body, a file when it has `data`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Part {
+    pub(crate) name: String,
+    // the text of a field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) filename: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) content_type: Option<String>,
+    // the base64 of the content of a file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) data: Option<String>,
+}
+
+macro_rules! impl_text_kind {
+    ($db:ty) => {
+        impl Type<$db> for BodyKind {
+            fn type_info() -> <$db as sqlx::Database>::TypeInfo {
+                <str as Type<$db>>::type_info()
+            }
+
+            fn compatible(ty: &<$db as sqlx::Database>::TypeInfo) -> bool {
+                <str as Type<$db>>::compatible(ty)
+            }
+        }
+
+        impl<'q> Encode<'q, $db> for BodyKind {
+            fn encode_by_ref(&self, buf: &mut <$db as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+                <&str as Encode<'q, $db>>::encode_by_ref(&self.as_str(), buf)
+            }
+        }
+
+        impl<'r> Decode<'r, $db> for BodyKind {
+            fn decode(value: <$db as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+                Ok(<&str as Decode<'r, $db>>::decode(value)?.parse()?)
+            }
+        }
+    };
+}
+
+impl_text_kind!(MySql);
+impl_text_kind!(Sqlite);
+impl_text_kind!(Postgres);
@@ -0,0 +1,114 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::api::TIMEOUT;
+
+// the redirects followed by default, as many as a browser follows.
+pub(crate) const MAX_REDIRECTS: usize = 10;
+// the bytes of a response body read by default, the rest is dropped.
+pub(crate) const MAX_BODY_BYTES: u64 = 10 * 1024 * 1024;
+
+// how the client sends a request, saved with the request. an option left out
+// falls back to the `client` section of the configuration, then to the
+// defaults of the client.
+//
+// `ca_bundle` and `client_cert` are PEM, the client certificate being followed
+// by its PKCS#8 private key. the timeouts are at most the one of the server,
+// which cuts off a longer execution.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ClientOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) connect_timeout_ms: Option<u64>,
+    // of the whole execution, from its first connection to the end of the last
+    // body, with its redirects, digest challenge and OAuth2 token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) redirect: Option<Redirect>,
+    // accept invalid certificates and host names
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) insecure: Option<bool>,
+    // the certificates trusted besides the built-in roots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) ca_bundle: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) client_cert: Option<String>,
+    // an http, https, socks5 or socks5h url
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) proxy: Option<String>,
+    // the bytes of a response body read, a longer body is cut and the
+    // response marked truncated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_body_bytes: Option<u64>,
+}
+
+// `"follow"`, `"none"` or `{ "max": 3 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Redirect {
+    Follow,
+    #[serde(rename = "none")]
+    Never,
+    Max(usize),
+}
+
+impl ClientOptions {
+    // the options given to the API, rejected when they can not take effect.
+    pub(crate) fn deserialize_checked<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<ClientOptions, D::Error> {
+        let options = ClientOptions::deserialize(deserializer)?;
+        options.check().map_err(serde::de::Error::custom)?;
+        Ok(options)
+    }
+
+    pub(crate) fn check(&self) -> Result<()> {
+        let limit = TIMEOUT.as_millis() as u64;
+        for (name, timeout) in [
+            ("connect_timeout_ms", self.connect_timeout_ms),
+            ("timeout_ms", self.timeout_ms),
+        ] {
+            if timeout.is_some_and(|timeout| timeout > limit) {
+                bail!("{} is above the limit of the server of {} ms", name, limit);
+            }
+        }
+        for (name, pem) in [
+            ("ca_bundle", &self.ca_bundle),
+            ("client_cert", &self.client_cert),
+        ] {
+            if pem
+                .as_ref()
+                .is_some_and(|pem| !pem.trim_start().starts_with("-----BEGIN"))
+            {
+                bail!("{} is not PEM", name);
+            }
+        }
+        Ok(())
+    }
+
+    // the options, with the ones left out taken from `fallback`.
+    pub(crate) fn or(self, fallback: &ClientOptions) -> ClientOptions {
+        ClientOptions {
+            connect_timeout_ms: self.connect_timeout_ms.or(fallback.connect_timeout_ms),
+            timeout_ms: self.timeout_ms.or(fallback.timeout_ms),
+            redirect: self.redirect.or(fallback.redirect),
+            insecure: self.insecure.or(fallback.insecure),
+            ca_bundle: self.ca_bundle.or_else(|| fallback.ca_bundle.clone()),
+            client_cert: self.client_cert.or_else(|| fallback.client_cert.clone()),
+            proxy: self.proxy.or_else(|| fallback.proxy.clone()),
+            max_body_bytes: self.max_body_bytes.or(fallback.max_body_bytes),
+        }
+    }
+
+    pub(crate) fn max_redirects(&self) -> usize {
+        match self.redirect {
+            None | Some(Redirect::Follow) => MAX_REDIRECTS,
+            Some(Redirect::Never) => 0,
+            Some(Redirect::Max(max)) => max,
+        }
+    }
+
+    pub(crate) fn max_body_bytes(&self) -> usize {
+        self.max_body_bytes.unwrap_or(MAX_BODY_BYTES) as usize
+    }
+}
@@ -0,0 +1,71 @@
+use crate::api::resp::FetchOne;
+use crate::entity::id::Id;
+use axum::response::IntoResponse;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use sqlx_crud::{add_timed_fields, SqlxCrud};
+
+// saved requests which are executed together, in order.
+#[add_timed_fields]
+#[derive(Debug, Clone, Serialize, FromRow, SqlxCrud, Default)]
+pub(crate) struct Collection {
+    pub(crate) id: Id,
+    pub(crate) workspace_id: Id,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    // the ids of the saved requests, in the order they are executed
+    pub(crate) requests: Value,
+}
+
+impl Collection {
+    pub(crate) fn request_ids(&self) -> Vec<Id> {
+        serde_json::from_value(self.requests.clone()).unwrap_or_default()
+    }
+}
+
+impl IntoResponse for Collection {
+    fn into_response(self) -> axum::response::Response {
+        FetchOne::new(self).into_response()
+    }
+}
+
+// one run of all the requests of a collection, saved when it starts and
+// updated after every step.
+#[derive(Debug, Clone, Serialize, FromRow, SqlxCrud, Default)]
+pub(crate) struct CollectionRun {
+    pub(crate) id: Id,
+    pub(crate) workspace_id: Id,
+    pub(crate) collection_id: Id,
+    pub(crate) environment_id: Option<Id>,
+    // the jar the requests share their cookies through
+    pub(crate) cookie_jar_id: Option<Id>,
+    pub(crate) stop_on_failure: bool,
+    // whether every step succeeded and passed its assertions, false until the
+    // run is finished
+    pub(crate) passed: bool,
+    pub(crate) started_at: DateTime<Local>,
+    // none while the requests are running
+    pub(crate) finished_at: Option<DateTime<Local>>,
+    // the `CollectionRunStep` of every request executed
+    pub(crate) steps: Value,
+}
+
+impl IntoResponse for CollectionRun {
+    fn into_response(self) -> axum::response::Response {
+        FetchOne::new(self).into_response()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CollectionRunStep {
+    pub(crate) request_id: Id,
+    pub(crate) execution_id: Option<Id>,
+    pub(crate) status_code: Option<i32>,
+    pub(crate) duration_ms: Option<i64>,
+    pub(crate) passed: bool,
+    // why the request could not be executed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
@@ -0,0 +1,60 @@
+use crate::api::resp::FetchOne;
+use crate::entity::id::Id;
+use axum::response::IntoResponse;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use sqlx_crud::{add_timed_fields, SqlxCrud};
+
+// the cookies the responses of executions set, sent again with the requests of
+// later executions selecting the jar, as a browser keeps a session.
+#[add_timed_fields]
+#[derive(Debug, Clone, Serialize, FromRow, SqlxCrud, Default)]
+pub(crate) struct CookieJar {
+    pub(crate) id: Id,
+    pub(crate) workspace_id: Id,
+    pub(crate) name: String,
+    // the `Cookie`s of the jar
+    pub(crate) cookies: Value,
+}
+
+impl CookieJar {
+    pub(crate) fn cookies(&self) -> Vec<Cookie> {
+        serde_json::from_value(self.cookies.clone()).unwrap_or_default()
+    }
+}
+
+impl IntoResponse for CookieJar {
+    fn into_response(self) -> axum::response::Response {
+        FetchOne::new(self).into_response()
+    }
+}
+
+// a cookie as set by a `Set-Cookie` header, a cookie is replaced by another
+// of the same name, domain and path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Cookie {
+    pub(crate) name: String,
+    pub(crate) value: String,
+    // the host the cookie was set by without a `Domain` attribute, or the
+    // domain whose subdomains it is sent to as well
+    pub(crate) domain: String,
+    // whether the cookie is only sent to the host which set it
+    #[serde(default)]
+    pub(crate) host_only: bool,
+    #[serde(default = "root")]
+    pub(crate) path: String,
+    // a cookie without an expiry lasts as long as the jar
+    #[serde(default)]
+    pub(crate) expires: Option<DateTime<Local>>,
+    // whether the cookie is only sent over https
+    #[serde(default)]
+    pub(crate) secure: bool,
+    #[serde(default)]
+    pub(crate) http_only: bool,
+}
+
+fn root() -> String {
+    "/".to_string()
+}
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use crate::api::resp::FetchOne;
+use crate::entity::id::Id;
+use axum::response::IntoResponse;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::FromRow;
+use sqlx_crud::{add_timed_fields, SqlxCrud};
+
+// a set of variables, such as the host of dev, staging or prod, substituted
+// for the `{{name}}` expressions of a request when it is executed.
+#[add_timed_fields]
+#[derive(Debug, Clone, Serialize, FromRow, SqlxCrud, Default)]
+pub(crate) struct Environment {
+    pub(crate) id: Id,
+    pub(crate) workspace_id: Id,
+    pub(crate) name: String,
+    // a JSON object of the variables by name
+    pub(crate) variables: Value,
+}
+
+impl Environment {
+    pub(crate) fn variables(&self) -> HashMap<String, String> {
+        self.variables
+            .as_object()
+            .map(|variables| {
+                variables
+                    .iter()
+                    .map(|(k, v)| match v {
+                        Value::String(s) => (k.clone(), s.clone()),
+                        other => (k.clone(), other.to_string()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl IntoResponse for Environment {
+    fn into_response(self) -> axum::response::Response {
+        FetchOne::new(self).into_response()
+    }
+}
@@ -1,19 +1,21 @@
 use crate::api::resp::FetchOne;
+use crate::entity::{body::BodyKind, id::Id};
 use axum::response::IntoResponse;
 use chrono::{DateTime, Local};
 use serde::Serialize;
 use serde_json::Value;
 use sqlx::FromRow;
 use sqlx_crud::SqlxCrud;
-use uuid::fmt::Hyphenated;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, FromRow, SqlxCrud)]
 pub(crate) struct RawHttpRequest {
-    pub(crate) id: u64,
+    pub(crate) id: Id,
     pub(crate) method: String,
     pub(crate) url: String,
     pub(crate) version: String,
     pub(crate) headers: Value,
+    // how the body was sent, see `BodyKind`
+    pub(crate) body_kind: BodyKind,
     pub(crate) body: Option<Value>,
 }
 
@@ -25,12 +27,23 @@ impl IntoResponse for RawHttpRequest {
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, FromRow, SqlxCrud)]
 pub(crate) struct RawHttpResponse {
-    pub(crate) id: u64,
+    pub(crate) id: Id,
     pub(crate) version: String,
-    pub(crate) status_code: u16,
+    pub(crate) status_code: i32,
     pub(crate) status_message: String,
     pub(crate) headers: Value,
+    // the size of the body in bytes
+    pub(crate) size: i64,
+    // the content type of the response, or the one detected from the body
+    pub(crate) content_type: String,
+    // the body decoded as JSON or text, null when it is binary
     pub(crate) body: Value,
+    // the body byte-for-byte, it is downloaded from its own endpoint
+    #[serde(skip)]
+    pub(crate) raw_body: Vec<u8>,
+    // whether the body was cut at the `max_body_bytes` of the client
+    #[serde(default)]
+    pub(crate) truncated: bool,
 }
 
 impl IntoResponse for RawHttpResponse {
@@ -41,11 +54,46 @@ impl IntoResponse for RawHttpResponse {
 
 #[derive(Debug, Clone, Serialize, FromRow, SqlxCrud, Default)]
 pub(crate) struct Execution {
-    pub(crate) id: Hyphenated,
-    pub(crate) request: u64,
+    pub(crate) id: Id,
+    pub(crate) workspace_id: Id,
+    pub(crate) request_id: Id,
+    // the environment whose variables the request was rendered with
+    pub(crate) environment_id: Option<Id>,
+    // the jar whose cookies the request was sent with
+    pub(crate) cookie_jar_id: Option<Id>,
+    pub(crate) request: Id,
     pub(crate) request_time: DateTime<Local>,
     pub(crate) response_time: DateTime<Local>,
-    pub(crate) response: u64,
+    pub(crate) response: Id,
+    // whether all the assertions of the request passed
+    pub(crate) passed: bool,
+    // the `AssertionResult` of every assertion
+    pub(crate) assertions: Value,
+    // a JSON object of the variables extracted from the response by name
+    pub(crate) extracted: Value,
+    // the `Hop`s of the redirects followed before the response, in order
+    pub(crate) redirects: Value,
+    // the `Timings` of the exchange of the response
+    pub(crate) timings: Value,
+    // the address the response came from, the proxy's when there is one
+    pub(crate) remote_addr: Option<String>,
+}
+
+// a redirect followed by an execution.
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub(crate) struct Hop {
+    pub(crate) method: String,
+    pub(crate) url: String,
+    pub(crate) status_code: i32,
+    pub(crate) status_message: String,
+    // where the redirect points to, resolved against the url
+    pub(crate) location: String,
+    pub(crate) headers: Value,
+    pub(crate) request_time: DateTime<Local>,
+    pub(crate) response_time: DateTime<Local>,
+    // the `Timings` of the exchange of the redirect, its body is not read
+    #[serde(default)]
+    pub(crate) timings: Timings,
 }
 
 impl IntoResponse for Execution {
@@ -53,3 +101,25 @@ impl IntoResponse for Execution {
         FetchOne::new(self).into_response()
     }
 }
+
+// how long the phases of the exchange of the response took in milliseconds. a
+// phase which did not happen, such as the lookup of an address or the TLS
+// handshake of http, is left out, and a response over a connection already
+// open has none of the phases of connecting.
+//
+// the phases are timed by the connector of the client on the connection the
+// response came over, see `connector::Connector`.
+#[derive(Debug, Clone, Default, Serialize, serde::Deserialize)]
+pub(crate) struct Timings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) dns_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) connect_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tls_ms: Option<f64>,
+    // from sending the request to receiving the headers of the response, the
+    // phases above included
+    pub(crate) ttfb_ms: f64,
+    // from the headers to the end of the body
+    pub(crate) download_ms: f64,
+}
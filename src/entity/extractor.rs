@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+// takes a value out of the response of an execution and saves it as the
+// variable `variable` of the workspace, saved with the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum Extractor {
+    // the first value the path selects in the JSON body
+    JsonPath {
+        variable: String,
+        path: String,
+    },
+    Header {
+        variable: String,
+        name: String,
+    },
+    // the capture group of the first match in the body, the whole match
+    // without a group
+    Regex {
+        variable: String,
+        pattern: String,
+        #[serde(default)]
+        group: Option<usize>,
+    },
+    // the value of a cookie set by the response
+    Cookie {
+        variable: String,
+        name: String,
+    },
+}
+
+impl Extractor {
+    pub(crate) fn variable(&self) -> &str {
+        match self {
+            Self::JsonPath { variable, .. }
+            | Self::Header { variable, .. }
+            | Self::Regex { variable, .. }
+            | Self::Cookie { variable, .. } => variable,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    database::{HasArguments, HasValueRef},
+    encode::IsNull,
+    error::BoxDynError,
+    mysql::MySql,
+    postgres::Postgres,
+    sqlite::Sqlite,
+    Decode, Encode, Type,
+};
+use uuid::{fmt::Hyphenated, Uuid};
+
+// the id of an entity.
+//
+// it is stored as the hyphenated text of the uuid in mysql and sqlite, and as
+// a native `UUID` in postgres, which `Hyphenated` does not support.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct Id(Uuid);
+
+impl Id {
+    pub(crate) const NIL: Id = Id(Uuid::nil());
+
+    pub(crate) fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl From<Uuid> for Id {
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+}
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.hyphenated().fmt(f)
+    }
+}
+
+macro_rules! impl_text_id {
+    ($db:ty) => {
+        impl Type<$db> for Id {
+            fn type_info() -> <$db as sqlx::Database>::TypeInfo {
+                <Hyphenated as Type<$db>>::type_info()
+            }
+
+            fn compatible(ty: &<$db as sqlx::Database>::TypeInfo) -> bool {
+                <Hyphenated as Type<$db>>::compatible(ty)
+            }
+        }
+
+        impl<'q> Encode<'q, $db> for Id {
+            fn encode_by_ref(&self, buf: &mut <$db as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+                <Hyphenated as Encode<'q, $db>>::encode_by_ref(&self.0.hyphenated(), buf)
+            }
+        }
+
+        impl<'r> Decode<'r, $db> for Id {
+            fn decode(value: <$db as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+                <Hyphenated as Decode<'r, $db>>::decode(value).map(|id| Self(id.into_uuid()))
+            }
+        }
+    };
+}
+
+impl_text_id!(MySql);
+impl_text_id!(Sqlite);
+
+impl Type<Postgres> for Id {
+    fn type_info() -> <Postgres as sqlx::Database>::TypeInfo {
+        <Uuid as Type<Postgres>>::type_info()
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for Id {
+    fn encode_by_ref(&self, buf: &mut <Postgres as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+        <Uuid as Encode<'q, Postgres>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Id {
+    fn decode(value: <Postgres as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+        <Uuid as Decode<'r, Postgres>>::decode(value).map(Self)
+    }
+}
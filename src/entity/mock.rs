@@ -0,0 +1,38 @@
+use crate::api::resp::FetchOne;
+use crate::entity::id::Id;
+use axum::response::IntoResponse;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::FromRow;
+use sqlx_crud::{add_timed_fields, SqlxCrud};
+
+// a canned response served under `/mock`.
+//
+// a route matches a request when the method is the same (or `*`), the path
+// matches the pattern, where `:name` matches any one segment and `*` the rest
+// of the path, every entry of `query` and `headers` is present with the given
+// value, and `body`, when set, is contained in the JSON body of the request.
+// when several routes match, the one with the highest priority wins.
+#[add_timed_fields]
+#[derive(Debug, Clone, Serialize, FromRow, SqlxCrud, Default)]
+pub(crate) struct MockRoute {
+    pub(crate) id: Id,
+    pub(crate) name: String,
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) query: Value,
+    pub(crate) headers: Value,
+    pub(crate) body: Option<Value>,
+    pub(crate) priority: i32,
+    pub(crate) status_code: i32,
+    pub(crate) response_headers: Value,
+    pub(crate) response_body: Option<String>,
+    pub(crate) delay_ms: i64,
+    pub(crate) hits: i64,
+}
+
+impl IntoResponse for MockRoute {
+    fn into_response(self) -> axum::response::Response {
+        FetchOne::new(self).into_response()
+    }
+}
@@ -1,3 +1,15 @@
-pub(crate) mod response;
+pub(crate) mod assertion;
+pub(crate) mod auth;
+pub(crate) mod bin;
+pub(crate) mod body;
+pub(crate) mod client;
+pub(crate) mod collection;
+pub(crate) mod cookie_jar;
+pub(crate) mod environment;
 pub(crate) mod execution;
+pub(crate) mod extractor;
+pub(crate) mod id;
+pub(crate) mod mock;
 pub(crate) mod request;
+pub(crate) mod variable;
+pub(crate) mod workspace;
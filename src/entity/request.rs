@@ -1,7 +1,7 @@
 use crate::api::resp::FetchOne;
+use crate::entity::{body::BodyKind, id::Id};
 use axum::response::IntoResponse;
 use serde::Serialize;
-use sqlx::types::uuid::fmt::Hyphenated;
 use sqlx::FromRow;
 use sqlx_crud::{add_timed_fields, SqlxCrud};
 
@@ -24,14 +24,24 @@ use sqlx_crud::{add_timed_fields, SqlxCrud};
 #[add_timed_fields]
 #[derive(Debug, Clone, Serialize, FromRow, SqlxCrud, Default)]
 pub(crate) struct Request {
-    pub(crate) id: Hyphenated,
+    pub(crate) id: Id,
+    pub(crate) workspace_id: Id,
     pub(crate) name: String,
     pub(crate) method: String,
     pub(crate) path: String,
     pub(crate) query: serde_json::Value,
     pub(crate) host: String,
     pub(crate) headers: serde_json::Value,
+    pub(crate) body_kind: BodyKind,
     pub(crate) body: Option<serde_json::Value>,
+    // the `Assertion`s checked against the response of every execution
+    pub(crate) assertions: serde_json::Value,
+    // the `Extractor`s saving values of the response of every execution
+    pub(crate) extractors: serde_json::Value,
+    // the `ClientOptions` the request is sent with
+    pub(crate) client_options: serde_json::Value,
+    // the `Auth` of the request
+    pub(crate) auth: Option<serde_json::Value>,
 }
 
 impl IntoResponse for Request {
@@ -0,0 +1,26 @@
+use crate::api::resp::FetchOne;
+use crate::entity::id::Id;
+use axum::response::IntoResponse;
+use serde::Serialize;
+use sqlx::FromRow;
+use sqlx_crud::{add_timed_fields, SqlxCrud};
+
+// a variable of a workspace, set by hand or extracted from the response of an
+// execution, available to every request of the workspace as `{{name}}` unless
+// the environment the request is executed with has a variable of the name.
+#[add_timed_fields]
+#[derive(Debug, Clone, Serialize, FromRow, SqlxCrud, Default)]
+pub(crate) struct Variable {
+    pub(crate) id: Id,
+    pub(crate) workspace_id: Id,
+    pub(crate) name: String,
+    pub(crate) value: String,
+    // the execution the value was extracted from, if any
+    pub(crate) execution_id: Option<Id>,
+}
+
+impl IntoResponse for Variable {
+    fn into_response(self) -> axum::response::Response {
+        FetchOne::new(self).into_response()
+    }
+}
@@ -0,0 +1,24 @@
+use crate::api::resp::FetchOne;
+use crate::entity::id::Id;
+use axum::response::IntoResponse;
+use serde::Serialize;
+use sqlx::FromRow;
+use sqlx_crud::{add_timed_fields, SqlxCrud};
+
+// the workspace every request falls into when none is selected, it is created
+// by the migrations and can not be deleted.
+pub(crate) const DEFAULT_WORKSPACE: Id = Id::NIL;
+
+#[add_timed_fields]
+#[derive(Debug, Clone, Serialize, FromRow, SqlxCrud, Default)]
+pub(crate) struct Workspace {
+    pub(crate) id: Id,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+}
+
+impl IntoResponse for Workspace {
+    fn into_response(self) -> axum::response::Response {
+        FetchOne::new(self).into_response()
+    }
+}
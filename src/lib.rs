@@ -6,3 +6,4 @@ pub(crate) mod db;
 pub(crate) mod entity;
 pub(crate) mod log;
 pub(crate) mod service;
+pub(crate) mod storage;
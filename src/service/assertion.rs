@@ -0,0 +1,238 @@
+use std::borrow::Cow;
+
+use anyhow::Result;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::{
+    entity::{
+        assertion::{Assertion, AssertionResult},
+        execution::RawHttpResponse,
+    },
+    service::mock,
+};
+
+// how much of the body a failed `BodyMatches` reports as found.
+const ACTUAL_LIMIT: usize = 256;
+
+// check the response against every assertion, an assertion which can not be
+// evaluated fails.
+pub(crate) fn evaluate(
+    assertions: &[Assertion],
+    response: &RawHttpResponse,
+    latency_ms: i64,
+) -> Vec<AssertionResult> {
+    assertions
+        .iter()
+        .map(|assertion| match check(assertion, response, latency_ms) {
+            Ok((passed, actual)) => AssertionResult {
+                assertion: assertion.clone(),
+                passed,
+                actual,
+                error: None,
+            },
+            Err(e) => AssertionResult {
+                assertion: assertion.clone(),
+                passed: false,
+                actual: Value::Null,
+                error: Some(e.to_string()),
+            },
+        })
+        .collect()
+}
+
+// whether the assertion passed, and what it found in the response.
+fn check(
+    assertion: &Assertion,
+    response: &RawHttpResponse,
+    latency_ms: i64,
+) -> Result<(bool, Value)> {
+    let status = response.status_code;
+    Ok(match assertion {
+        Assertion::StatusEquals { status: expected } => {
+            (status == i32::from(*expected), status.into())
+        }
+        Assertion::StatusInRange { min, max } => (
+            (i32::from(*min)..=i32::from(*max)).contains(&status),
+            status.into(),
+        ),
+        Assertion::HeaderPresent { name } => match header(response, name) {
+            Some(value) => (true, value.clone()),
+            None => (false, Value::Null),
+        },
+        Assertion::HeaderMatches { name, pattern } => {
+            let regex = Regex::new(pattern)?;
+            match header(response, name) {
+                Some(value) => (regex.is_match(&text(value)), value.clone()),
+                None => (false, Value::Null),
+            }
+        }
+        Assertion::JsonPathEquals { path, value } => {
+            let found = jsonpath_lib::select(&response.body, path)?;
+            (found.contains(&value), matched(found))
+        }
+        Assertion::JsonPathContains { path, value } => {
+            let found = jsonpath_lib::select(&response.body, path)?;
+            (found.iter().any(|v| contains(v, value)), matched(found))
+        }
+        Assertion::BodyMatches { pattern } => {
+            let body = body_text(response);
+            match Regex::new(pattern)?.find(&body) {
+                Some(found) => (true, found.as_str().into()),
+                None => (false, truncate(&body, ACTUAL_LIMIT).into()),
+            }
+        }
+        Assertion::LatencyBelow { ms } => (latency_ms < *ms, latency_ms.into()),
+    })
+}
+
+pub(crate) fn header<'a>(response: &'a RawHttpResponse, name: &str) -> Option<&'a Value> {
+    response
+        .headers
+        .as_object()?
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v)
+}
+
+pub(crate) fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+// the body as received, decoded as UTF-8 where it is not.
+pub(crate) fn body_text(response: &RawHttpResponse) -> Cow<'_, str> {
+    String::from_utf8_lossy(&response.raw_body)
+}
+
+// the text cut down to at most `limit` bytes, at a character boundary.
+fn truncate(text: &str, limit: usize) -> &str {
+    let mut end = limit.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+// a single match as it is, several of them as an array.
+fn matched(found: Vec<&Value>) -> Value {
+    match found[..] {
+        [] => Value::Null,
+        [value] => value.clone(),
+        _ => Value::Array(found.into_iter().cloned().collect()),
+    }
+}
+
+fn contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::String(actual), Value::String(expected)) => actual.contains(expected.as_str()),
+        (Value::Array(actual), expected) => actual.iter().any(|v| v == expected),
+        (actual, expected) => mock::contains(actual, expected),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::entity::id::Id;
+
+    use super::*;
+
+    fn response(headers: Value, body: &[u8]) -> RawHttpResponse {
+        RawHttpResponse {
+            id: Id::new(),
+            version: "HTTP/1.1".to_string(),
+            status_code: 201,
+            status_message: "Created".to_string(),
+            headers,
+            size: body.len() as i64,
+            content_type: "application/json".to_string(),
+            body: serde_json::from_slice(body).unwrap_or(Value::Null),
+            raw_body: body.to_vec(),
+            truncated: false,
+        }
+    }
+
+    fn assertion(value: Value) -> Assertion {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn status_header_and_latency() {
+        let response = response(
+            json!({"Content-Type": "application/json", "set-cookie": ["a=1", "b=2"]}),
+            b"{}",
+        );
+        let assertions = [
+            json!({"type": "status_equals", "status": 201}),
+            json!({"type": "status_in_range", "min": 200, "max": 299}),
+            json!({"type": "status_equals", "status": 200}),
+            json!({"type": "header_present", "name": "content-type"}),
+            json!({"type": "header_present", "name": "etag"}),
+            json!({"type": "header_matches", "name": "CONTENT-TYPE", "pattern": "^application/"}),
+            json!({"type": "header_matches", "name": "set-cookie", "pattern": "b=2"}),
+            json!({"type": "latency_below", "ms": 100}),
+            json!({"type": "latency_below", "ms": 10}),
+        ]
+        .map(assertion);
+        let results = evaluate(&assertions, &response, 50);
+        let passed = results.iter().map(|r| r.passed).collect::<Vec<_>>();
+        assert_eq!(
+            passed,
+            [true, true, false, true, false, true, true, true, false]
+        );
+        assert_eq!(results[0].actual, json!(201));
+        assert_eq!(results[3].actual, json!("application/json"));
+        assert_eq!(results[7].actual, json!(50));
+    }
+
+    #[test]
+    fn json_path() {
+        let response = response(
+            json!({}),
+            br#"{"user": {"name": "ann", "roles": ["admin", "dev"]}, "tags": [1, 2]}"#,
+        );
+        let assertions = [
+            json!({"type": "json_path_equals", "path": "$.user.name", "value": "ann"}),
+            json!({"type": "json_path_equals", "path": "$.tags[*]", "value": 2}),
+            json!({"type": "json_path_contains", "path": "$.user.name", "value": "nn"}),
+            json!({"type": "json_path_contains", "path": "$.user.roles", "value": "dev"}),
+            json!({"type": "json_path_contains", "path": "$.user", "value": {"name": "ann"}}),
+            json!({"type": "json_path_equals", "path": "$.missing", "value": 1}),
+            json!({"type": "json_path_equals", "path": "$[", "value": 1}),
+        ]
+        .map(assertion);
+        let results = evaluate(&assertions, &response, 0);
+        let passed = results.iter().map(|r| r.passed).collect::<Vec<_>>();
+        assert_eq!(passed, [true, true, true, true, true, false, false]);
+        assert_eq!(results[1].actual, json!([1, 2]));
+        assert_eq!(results[5].actual, Value::Null);
+        assert!(results[6].error.is_some());
+    }
+
+    #[test]
+    fn body_matches_the_raw_body() {
+        // the body is not JSON, and not valid UTF-8 either
+        let mut body = b"ok: token=abc123 ".to_vec();
+        body.extend([0xff, 0xfe]);
+        body.extend("é".repeat(300).as_bytes());
+        let response = response(json!({}), &body);
+        let assertions = [
+            json!({"type": "body_matches", "pattern": "token=[a-z0-9]+"}),
+            json!({"type": "body_matches", "pattern": "missing"}),
+            json!({"type": "body_matches", "pattern": "("}),
+        ]
+        .map(assertion);
+        let results = evaluate(&assertions, &response, 0);
+        assert!(results[0].passed);
+        assert_eq!(results[0].actual, json!("token=abc123"));
+        assert!(!results[1].passed);
+        let actual = results[1].actual.as_str().unwrap();
+        assert!(actual.starts_with("ok: token=abc123 \u{fffd}\u{fffd}é"));
+        assert!(actual.len() <= ACTUAL_LIMIT);
+        assert!(results[2].error.is_some());
+    }
+}
@@ -0,0 +1,272 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::Value;
+
+use crate::{
+    entity::body::{BodyKind, Part},
+    service::assertion::text,
+};
+
+// the fields of a form body, kept as an object or as the encoded string.
+pub(crate) fn form_fields(body: &Value) -> Result<Vec<(String, String)>> {
+    Ok(match body {
+        Value::Object(fields) => fields.iter().map(|(k, v)| (k.clone(), text(v))).collect(),
+        Value::String(form) => serde_urlencoded::from_str(form)?,
+        other => {
+            return Err(anyhow!(
+                "a form body is an object or a string, not {}",
+                other
+            ))
+        }
+    })
+}
+
+pub(crate) fn parts(body: &Value) -> Result<Vec<Part>> {
+    serde_json::from_value(body.clone())
+        .map_err(|e| anyhow!("a multipart body is an array of parts: {}", e))
+}
+
+// the body as it is sent, a binary body is its base64 and a multipart body has
+// no text.
+pub(crate) fn to_text(kind: BodyKind, body: &Value) -> Result<String> {
+    Ok(match kind {
+        BodyKind::Json => serde_json::to_string(body)?,
+        BodyKind::Text | BodyKind::Binary => text(body),
+        BodyKind::Form => serde_urlencoded::to_string(form_fields(body)?)?,
+        BodyKind::Multipart => String::new(),
+    })
+}
+
+// the bytes of the body as they are sent, a multipart body has none as they
+// depend on its boundary, see `encode`.
+pub(crate) fn to_bytes(kind: BodyKind, body: &Value) -> Result<Option<Vec<u8>>> {
+    Ok(match kind {
+        BodyKind::Binary => Some(STANDARD.decode(text(body))?),
+        BodyKind::Multipart => None,
+        kind => Some(to_text(kind, body)?.into_bytes()),
+    })
+}
+
+// the content type set by the headers, if any.
+pub(crate) fn declared_type(headers: &Value) -> Option<String> {
+    let headers = headers.as_object()?;
+    let (_, value) = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))?;
+    Some(text(value))
+}
+
+// the content type the body is sent with, the declared one or the one of its
+// kind. a multipart body keeps the declared boundary or gets a new one.
+pub(crate) fn sent_type(kind: BodyKind, declared: Option<&str>) -> String {
+    match declared {
+        Some(declared) if kind != BodyKind::Multipart || boundary(declared).is_some() => {
+            declared.to_string()
+        }
+        _ if kind == BodyKind::Multipart => format!(
+            "{}; boundary={:032x}",
+            kind.content_type(),
+            rand::random::<u128>()
+        ),
+        _ => kind.content_type().to_string(),
+    }
+}
+
+// the `boundary` parameter of a multipart content type.
+fn boundary(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| value.trim().trim_matches('"'))
+            .filter(|boundary| !boundary.is_empty())
+    })
+}
+
+// the bytes of the body as it is sent and its content type, see `sent_type`.
+pub(crate) fn encode(
+    kind: BodyKind,
+    body: &Value,
+    declared: Option<&str>,
+) -> Result<(Vec<u8>, String)> {
+    let content_type = sent_type(kind, declared);
+    let bytes = match boundary(&content_type).filter(|_| kind == BodyKind::Multipart) {
+        Some(boundary) => multipart(&parts(body)?, boundary)?,
+        None => to_bytes(kind, body)?.unwrap_or_default(),
+    };
+    Ok((bytes, content_type))
+}
+
+// a name or file name within the quotes of a `Content-Disposition`, escaped
+// the way browsers do.
+fn quote(name: &str) -> String {
+    name.replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn multipart(parts: &[Part], boundary: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for part in parts {
+        let mut head = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"",
+            boundary,
+            quote(&part.name)
+        );
+        if let Some(filename) = &part.filename {
+            head.push_str(&format!("; filename=\"{}\"", quote(filename)));
+        }
+        if let Some(content_type) = &part.content_type {
+            head.push_str(&format!("\r\nContent-Type: {}", content_type));
+        }
+        head.push_str("\r\n\r\n");
+        bytes.extend_from_slice(head.as_bytes());
+        match (&part.data, &part.value) {
+            (Some(data), _) => bytes.extend(STANDARD.decode(data)?),
+            (None, value) => {
+                bytes.extend_from_slice(value.as_deref().unwrap_or_default().as_bytes())
+            }
+        }
+        bytes.extend_from_slice(b"\r\n");
+    }
+    bytes.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    Ok(bytes)
+}
+
+// the signatures of the binary formats recognized in a response without a
+// content type.
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"PK\x03\x04", "application/zip"),
+];
+
+// the content type of a response body, the declared one unless it is missing,
+// an empty body has none.
+pub(crate) fn content_type(declared: Option<&str>, bytes: &[u8]) -> String {
+    if let Some(declared) = declared.filter(|declared| !declared.is_empty()) {
+        return declared.to_string();
+    }
+    if bytes.is_empty() {
+        return String::new();
+    }
+    if let Some((_, content_type)) = SIGNATURES
+        .iter()
+        .find(|(signature, _)| bytes.starts_with(signature))
+    {
+        return content_type.to_string();
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) if serde_json::from_str::<Value>(text).is_ok() => "application/json",
+        Ok(_) => BodyKind::Text.content_type(),
+        Err(_) => BodyKind::Binary.content_type(),
+    }
+    .to_string()
+}
+
+// the body as JSON when it is of a JSON content type and parses, as text when
+// it is UTF-8 and null otherwise.
+pub(crate) fn decode(content_type: &str, bytes: &[u8]) -> Value {
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return Value::Null;
+    };
+    if content_type.to_lowercase().contains("json") {
+        if let Ok(json) = serde_json::from_str(text) {
+            return json;
+        }
+    }
+    Value::String(text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn encode_kinds() {
+        let (bytes, content_type) = encode(BodyKind::Json, &json!({ "a": 1 }), None).unwrap();
+        assert_eq!(bytes, br#"{"a":1}"#);
+        assert_eq!(content_type, "application/json");
+
+        let form = json!({ "a": "1 2", "b": "&" });
+        let (bytes, content_type) = encode(BodyKind::Form, &form, None).unwrap();
+        assert_eq!(bytes, b"a=1+2&b=%26");
+        assert_eq!(content_type, "application/x-www-form-urlencoded");
+        assert_eq!(
+            form_fields(&json!("a=1+2&b=%26")).unwrap(),
+            [
+                ("a".to_string(), "1 2".to_string()),
+                ("b".to_string(), "&".to_string())
+            ]
+        );
+        assert!(form_fields(&json!([1])).is_err());
+
+        // the declared content type is kept
+        let (bytes, content_type) =
+            encode(BodyKind::Text, &json!("<a/>"), Some("application/xml")).unwrap();
+        assert_eq!(bytes, b"<a/>");
+        assert_eq!(content_type, "application/xml");
+
+        let (bytes, _) = encode(BodyKind::Binary, &json!("AAEC"), None).unwrap();
+        assert_eq!(bytes, [0, 1, 2]);
+        assert_eq!(to_text(BodyKind::Binary, &json!("AAEC")).unwrap(), "AAEC");
+    }
+
+    #[test]
+    fn encode_multipart_with_the_declared_boundary() {
+        let parts = json!([
+            { "name": "note", "value": "hi" },
+            { "name": "file", "filename": "a\"b.txt", "content_type": "text/plain", "data": "eA==" }
+        ]);
+        let declared = "multipart/form-data; boundary=\"xyz\"";
+        let (bytes, content_type) = encode(BodyKind::Multipart, &parts, Some(declared)).unwrap();
+        assert_eq!(content_type, declared);
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "--xyz\r\nContent-Disposition: form-data; name=\"note\"\r\n\r\nhi\r\n\
+             --xyz\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a%22b.txt\"\r\n\
+             Content-Type: text/plain\r\n\r\nx\r\n--xyz--\r\n"
+        );
+        assert!(to_bytes(BodyKind::Multipart, &parts).unwrap().is_none());
+    }
+
+    #[test]
+    fn multipart_gets_a_boundary() {
+        let content_type = sent_type(BodyKind::Multipart, Some("multipart/form-data"));
+        let boundary = boundary(&content_type).unwrap();
+        assert_eq!(boundary.len(), 32);
+        assert_eq!(
+            content_type,
+            format!("multipart/form-data; boundary={}", boundary)
+        );
+        assert_ne!(sent_type(BodyKind::Multipart, None), content_type);
+        assert_eq!(
+            declared_type(&json!({ "content-TYPE": "text/csv" })).as_deref(),
+            Some("text/csv")
+        );
+        assert_eq!(declared_type(&json!({})), None);
+    }
+
+    #[test]
+    fn detect_and_decode_responses() {
+        assert_eq!(content_type(Some("text/html"), b"{}"), "text/html");
+        assert_eq!(content_type(None, b""), "");
+        assert_eq!(content_type(None, b"\x89PNG\r\n\x1a\n...."), "image/png");
+        assert_eq!(content_type(None, b"[1]"), "application/json");
+        assert_eq!(content_type(None, b"hello"), BodyKind::Text.content_type());
+        assert_eq!(
+            content_type(None, b"\xff\xfe"),
+            BodyKind::Binary.content_type()
+        );
+        assert_eq!(decode("application/json", b"[1]"), json!([1]));
+        assert_eq!(decode("application/json", b"[1"), json!("[1"));
+        assert_eq!(decode("text/plain", b"[1]"), json!("[1]"));
+        assert_eq!(decode("text/plain", b"\xff"), Value::Null);
+    }
+}
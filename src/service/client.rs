@@ -0,0 +1,207 @@
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use hyper::{
+    body::HttpBody,
+    header::{HeaderValue, PROXY_AUTHORIZATION},
+    Body, Request, Response,
+};
+use native_tls::{Certificate, Identity, TlsConnector};
+use tokio::time::timeout_at;
+
+use crate::{
+    entity::client::ClientOptions,
+    service::{
+        connector::{Connector, Proxy},
+        timing::{PhaseTimer, Phases},
+    },
+};
+
+// the client sending requests with the options. it does not follow redirects
+// so that the execution records every hop, and its connector times the
+// connections it opens.
+#[derive(Clone)]
+pub(crate) struct Client {
+    http: hyper::Client<Connector>,
+    timer: PhaseTimer,
+    proxy: Option<Proxy>,
+    // of an execution, see `deadline`
+    timeout: Option<Duration>,
+    // the bytes of a response body read
+    max_body: usize,
+}
+
+impl Client {
+    pub(crate) fn build(options: &ClientOptions) -> Result<Client> {
+        let mut tls = TlsConnector::builder();
+        if options.insecure == Some(true) {
+            tls.danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true);
+        }
+        if let Some(bundle) = &options.ca_bundle {
+            for certificate in blocks(bundle, "CERTIFICATE") {
+                tls.add_root_certificate(Certificate::from_pem(certificate.as_bytes())?);
+            }
+        }
+        if let Some(cert) = &options.client_cert {
+            let key = blocks(cert, "PRIVATE KEY")
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    anyhow!("invalid client_cert: the certificate is not followed by a PKCS#8 key")
+                })?;
+            let chain = blocks(cert, "CERTIFICATE").concat();
+            tls.identity(Identity::from_pkcs8(chain.as_bytes(), key.as_bytes())?);
+        }
+        let proxy = options.proxy.as_deref().map(Proxy::parse).transpose()?;
+        let timer = PhaseTimer::default();
+        let connector = Connector {
+            tls: tls.build()?.into(),
+            proxy: proxy.clone(),
+            connect_timeout: options.connect_timeout_ms.map(Duration::from_millis),
+            timer: timer.clone(),
+        };
+        Ok(Client {
+            http: hyper::Client::builder().build(connector),
+            timer,
+            proxy,
+            timeout: options.timeout_ms.map(Duration::from_millis),
+            max_body: options.max_body_bytes(),
+        })
+    }
+
+    // when an execution starting now times out, none without a timeout. the
+    // execution passes it to all it sends, its redirects, the answer to a
+    // digest challenge and the fetch of an OAuth2 token share it.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.timeout.map(|timeout| Instant::now() + timeout)
+    }
+
+    // send the request, the response is returned once its headers are.
+    pub(crate) async fn send(
+        &self,
+        mut request: Request<Body>,
+        deadline: Option<Instant>,
+    ) -> Result<Response<Body>> {
+        // an http proxy is sent the requests of http urls as they are
+        let authorization = self
+            .proxy
+            .as_ref()
+            .filter(|proxy| proxy.forwards_http() && request.uri().scheme_str() == Some("http"))
+            .and_then(Proxy::authorization);
+        if let Some(authorization) = authorization {
+            request
+                .headers_mut()
+                .insert(PROXY_AUTHORIZATION, HeaderValue::from_str(&authorization)?);
+        }
+        Ok(self.within(deadline, self.http.request(request)).await??)
+    }
+
+    // the body of a response, and whether it was cut at the most bytes read.
+    pub(crate) async fn read(
+        &self,
+        body: Body,
+        deadline: Option<Instant>,
+    ) -> Result<(Vec<u8>, bool)> {
+        self.within(deadline, read_at_most(body, self.max_body))
+            .await?
+    }
+
+    async fn within<T>(
+        &self,
+        deadline: Option<Instant>,
+        future: impl Future<Output = T>,
+    ) -> Result<T> {
+        match deadline {
+            Some(deadline) => timeout_at(deadline.into(), future).await.map_err(|_| {
+                anyhow!(
+                    "the request timed out after {:?}",
+                    self.timeout.unwrap_or_default()
+                )
+            }),
+            None => Ok(future.await),
+        }
+    }
+
+    // the phases of the connection the last request opened, none when it was
+    // sent over one already open.
+    pub(crate) fn take_phases(&self) -> Option<Phases> {
+        self.timer.take()
+    }
+}
+
+// the first `limit` bytes of the body and whether it has more, which are not
+// read.
+async fn read_at_most(mut body: Body, limit: usize) -> Result<(Vec<u8>, bool)> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        let room = limit - bytes.len();
+        if chunk.len() > room {
+            bytes.extend_from_slice(&chunk[..room]);
+            return Ok((bytes, true));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok((bytes, false))
+}
+
+// the blocks of the label, such as the certificates of a bundle, in a PEM
+// which holds one block after another.
+fn blocks(pem: &str, label: &str) -> Vec<String> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    pem.split(begin.as_str())
+        .skip(1)
+        .filter_map(|block| {
+            let (body, _) = block.split_once(end.as_str())?;
+            Some(format!("{}{}{}\n", begin, body, end))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn read_at_most_the_limit() {
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move {
+            for chunk in ["hello", " ", "world"] {
+                sender.send_data(chunk.into()).await.unwrap();
+            }
+        });
+        assert_eq!(
+            read_at_most(body, 8).await.unwrap(),
+            (b"hello wo".to_vec(), true)
+        );
+        assert_eq!(
+            read_at_most(Body::from("hello"), 5).await.unwrap(),
+            (b"hello".to_vec(), false)
+        );
+        assert_eq!(
+            read_at_most(Body::empty(), 0).await.unwrap(),
+            (Vec::new(), false)
+        );
+    }
+
+    #[tokio::test]
+    async fn read_within_the_deadline() {
+        let client = Client::build(&ClientOptions {
+            timeout_ms: Some(20),
+            ..Default::default()
+        })
+        .unwrap();
+        let deadline = client.deadline();
+        // a body which never ends
+        let (_sender, body) = Body::channel();
+        let started = Instant::now();
+        assert!(client.read(body, deadline).await.is_err());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(client.read(Body::from("x"), None).await.is_ok());
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use chrono::Local;
+use sqlx_crud::Schema;
+
+use crate::{
+    api::{error::Error, resp::ExpectRowsAffected},
+    entity::{
+        collection::{Collection, CollectionRun, CollectionRunStep},
+        environment::Environment,
+        execution::RawHttpResponse,
+        id::Id,
+    },
+    service::{
+        cookie,
+        event::{Action, Event},
+        execution,
+    },
+    storage::{store, Storage},
+};
+
+// start running the requests of the collection one after another, the run is
+// saved before the first request and updated after every one of them. a
+// request which can not be executed or fails its assertions fails the run
+// and, with `stop_on_failure`, skips the requests after it. with a cookie jar,
+// the cookies a request is set are sent by the requests after it.
+pub(crate) async fn run_collection(
+    collection: &Collection,
+    environment_id: Option<Id>,
+    cookie_jar_id: Option<Id>,
+    stop_on_failure: bool,
+) -> Result<CollectionRun> {
+    // the run does not start with an environment or a jar it can not use
+    if let Some(environment_id) = environment_id {
+        store()
+            .by_id::<Environment>(environment_id)
+            .await?
+            .filter(|environment| environment.workspace_id == collection.workspace_id)
+            .ok_or_else(|| Error::NotFound)?;
+    }
+    cookie::jar(collection.workspace_id, cookie_jar_id).await?;
+    let run = CollectionRun {
+        id: Id::new(),
+        workspace_id: collection.workspace_id,
+        collection_id: collection.id,
+        environment_id,
+        cookie_jar_id,
+        stop_on_failure,
+        passed: false,
+        started_at: Local::now(),
+        finished_at: None,
+        steps: serde_json::json!([]),
+    };
+    store()
+        .create(run.clone())
+        .await
+        .map_err(|e| Error::CreateFailed(e.to_string()))?
+        .expect(1)?;
+    publish(&run, Action::Created);
+    let request_ids = collection.request_ids();
+    let mut started = run.clone();
+    tokio::spawn(async move {
+        if let Err(e) = run_steps(&mut started, request_ids).await {
+            tracing::error!("collection run {} failed: {}", started.id, e);
+            // the run is finished as failed whatever went wrong, so that it
+            // does not look like it is still running
+            started.passed = false;
+            started.finished_at = Some(Local::now());
+            if let Err(e) = save(&started).await {
+                tracing::error!("saving collection run {} failed: {}", started.id, e);
+            }
+        }
+    });
+    Ok(run)
+}
+
+async fn run_steps(run: &mut CollectionRun, request_ids: Vec<Id>) -> Result<()> {
+    let mut steps = Vec::new();
+    for request_id in request_ids {
+        let step = run_step(
+            run.workspace_id,
+            request_id,
+            run.environment_id,
+            run.cookie_jar_id,
+        )
+        .await;
+        let passed = step.passed;
+        steps.push(step);
+        if !passed && run.stop_on_failure {
+            tracing::info!(
+                "collection {} stopped at request {}",
+                run.collection_id,
+                request_id
+            );
+            break;
+        }
+        run.steps = serde_json::to_value(&steps)?;
+        save(run).await?;
+    }
+    run.steps = serde_json::to_value(&steps)?;
+    run.passed = steps.iter().all(|step| step.passed);
+    run.finished_at = Some(Local::now());
+    save(run).await
+}
+
+async fn save(run: &CollectionRun) -> Result<()> {
+    store().update(run.clone()).await?.expect(1)?;
+    publish(run, Action::Updated);
+    Ok(())
+}
+
+fn publish(run: &CollectionRun, action: Action) {
+    Event::new(CollectionRun::table_name(), action, run.id.to_string(), run)
+        .in_workspace(run.workspace_id)
+        .publish();
+}
+
+async fn run_step(
+    workspace_id: Id,
+    request_id: Id,
+    environment_id: Option<Id>,
+    cookie_jar_id: Option<Id>,
+) -> CollectionRunStep {
+    let execution =
+        match execution::execute_request(workspace_id, request_id, environment_id, cookie_jar_id)
+            .await
+        {
+            Ok(execution) => execution,
+            Err(e) => {
+                return CollectionRunStep {
+                    request_id,
+                    execution_id: None,
+                    status_code: None,
+                    duration_ms: None,
+                    passed: false,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+    let status_code = store()
+        .by_id::<RawHttpResponse>(execution.response)
+        .await
+        .ok()
+        .flatten()
+        .map(|response| response.status_code);
+    CollectionRunStep {
+        request_id,
+        execution_id: Some(execution.id),
+        status_code,
+        duration_ms: Some((execution.response_time - execution.request_time).num_milliseconds()),
+        passed: execution.passed,
+        error: None,
+    }
+}
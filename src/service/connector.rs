@@ -0,0 +1,335 @@
+use std::{
+    future::Future,
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hyper::{
+    client::connect::{Connected, Connection},
+    service::Service,
+    Uri,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf},
+    net::{lookup_host, TcpStream},
+    time::timeout,
+};
+use tokio_native_tls::TlsConnector;
+use tokio_socks::{tcp::Socks5Stream, IntoTargetAddr};
+use url::Url;
+
+use crate::service::timing::{PhaseTimer, Phases};
+
+// the most a proxy may answer to a `CONNECT` before the tunnel is open.
+const MAX_TUNNEL_RESPONSE: usize = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProxyKind {
+    Http,
+    Https,
+    // the proxy is sent the address of the host, which is looked up first
+    Socks5,
+    // the proxy is sent the name of the host
+    Socks5h,
+}
+
+// the proxy of the `proxy` option, an http, https, socks5 or socks5h url with
+// the credentials of the proxy, if any.
+#[derive(Debug, Clone)]
+pub(crate) struct Proxy {
+    kind: ProxyKind,
+    host: String,
+    port: u16,
+    credentials: Option<(String, String)>,
+}
+
+impl Proxy {
+    pub(crate) fn parse(proxy: &str) -> Result<Proxy> {
+        let url = Url::parse(proxy).map_err(|e| anyhow!("invalid proxy {}: {}", proxy, e))?;
+        let kind = match url.scheme() {
+            "http" => ProxyKind::Http,
+            "https" => ProxyKind::Https,
+            "socks5" => ProxyKind::Socks5,
+            "socks5h" => ProxyKind::Socks5h,
+            other => bail!("unsupported proxy scheme {}", other),
+        };
+        let host = url
+            .host_str()
+            .ok_or_else(|| anyhow!("the proxy {} has no host", proxy))?
+            .to_string();
+        let credentials = (!url.username().is_empty()).then(|| {
+            (
+                url.username().to_string(),
+                url.password().unwrap_or_default().to_string(),
+            )
+        });
+        Ok(Proxy {
+            kind,
+            host,
+            port: url.port_or_known_default().unwrap_or(1080),
+            credentials,
+        })
+    }
+
+    // the `Proxy-Authorization` of an http proxy with credentials.
+    pub(crate) fn authorization(&self) -> Option<String> {
+        match self.kind {
+            ProxyKind::Http | ProxyKind::Https => {
+                self.credentials.as_ref().map(|(username, password)| {
+                    format!(
+                        "Basic {}",
+                        STANDARD.encode(format!("{}:{}", username, password))
+                    )
+                })
+            }
+            ProxyKind::Socks5 | ProxyKind::Socks5h => None,
+        }
+    }
+
+    // whether the requests of http urls are sent to the proxy as they are,
+    // rather than through a tunnel.
+    pub(crate) fn forwards_http(&self) -> bool {
+        matches!(self.kind, ProxyKind::Http | ProxyKind::Https)
+    }
+}
+
+trait Io: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Io for T {}
+
+// the address a response came from, in the extensions of the response.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RemoteAddr(pub(crate) SocketAddr);
+
+// a connection opened by the connector.
+pub(crate) struct Conn {
+    io: Box<dyn Io>,
+    addr: SocketAddr,
+    // whether the requests are sent to an http proxy as they are
+    proxied: bool,
+}
+
+impl Connection for Conn {
+    fn connected(&self) -> Connected {
+        Connected::new()
+            .proxy(self.proxied)
+            .extra(RemoteAddr(self.addr))
+    }
+}
+
+impl AsyncRead for Conn {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Conn {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+    }
+}
+
+// opens the connections of a client, through the proxy if there is one, and
+// records how long looking the host up, connecting and the TLS handshake took
+// on the connection itself.
+#[derive(Clone)]
+pub(crate) struct Connector {
+    pub(crate) tls: TlsConnector,
+    pub(crate) proxy: Option<Proxy>,
+    // of all the phases together
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) timer: PhaseTimer,
+}
+
+impl Service<Uri> for Connector {
+    type Response = Conn;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = Result<Conn, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let connector = self.clone();
+        Box::pin(async move {
+            let connect = connector.connect(&uri);
+            let conn = match connector.connect_timeout {
+                Some(limit) => timeout(limit, connect).await.unwrap_or_else(|_| {
+                    Err(anyhow!("connecting to {} timed out after {:?}", uri, limit))
+                }),
+                None => connect.await,
+            };
+            Ok::<_, Self::Error>(conn?)
+        })
+    }
+}
+
+impl Connector {
+    async fn connect(&self, uri: &Uri) -> Result<Conn> {
+        let https = uri.scheme_str() == Some("https");
+        let host = uri.host().ok_or_else(|| anyhow!("{} has no host", uri))?;
+        let port = uri.port_u16().unwrap_or(if https { 443 } else { 80 });
+        let started = Instant::now();
+        let mut dns = None;
+        let (mut io, addr, proxied) = match &self.proxy {
+            None => {
+                let (stream, addr) = tcp(host, port, &mut dns).await?;
+                (Box::new(stream) as Box<dyn Io>, addr, false)
+            }
+            Some(proxy) => {
+                let (stream, addr) = tcp(&proxy.host, proxy.port, &mut dns).await?;
+                let mut io: Box<dyn Io> = match proxy.kind {
+                    ProxyKind::Https => {
+                        Box::new(self.tls.connect(unbracket(&proxy.host), stream).await?)
+                    }
+                    _ => Box::new(stream),
+                };
+                let credentials = proxy.credentials.as_ref();
+                match proxy.kind {
+                    ProxyKind::Http | ProxyKind::Https if !https => {}
+                    ProxyKind::Http | ProxyKind::Https => {
+                        tunnel(&mut *io, host, port, proxy.authorization()).await?
+                    }
+                    ProxyKind::Socks5 => {
+                        let target = lookup(host, port, &mut dns).await?[0];
+                        io = socks(io, target, credentials).await?;
+                    }
+                    ProxyKind::Socks5h => {
+                        io = socks(io, (unbracket(host), port), credentials).await?;
+                    }
+                }
+                (io, addr, !https && proxy.forwards_http())
+            }
+        };
+        let connect = started.elapsed().saturating_sub(dns.unwrap_or_default());
+        let tls = if https {
+            let started = Instant::now();
+            io = Box::new(self.tls.connect(unbracket(host), io).await?);
+            Some(started.elapsed())
+        } else {
+            None
+        };
+        self.timer.record(Phases { dns, connect, tls });
+        Ok(Conn { io, addr, proxied })
+    }
+}
+
+// the host of an url without the brackets of an IPv6 address.
+fn unbracket(host: &str) -> &str {
+    host.trim_start_matches('[').trim_end_matches(']')
+}
+
+// the addresses of the host, how long looking them up took is added to `dns`.
+async fn lookup(host: &str, port: u16, dns: &mut Option<Duration>) -> Result<Vec<SocketAddr>> {
+    let host = unbracket(host);
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(vec![SocketAddr::new(ip, port)]);
+    }
+    let started = Instant::now();
+    let addrs = lookup_host((host, port)).await?.collect::<Vec<_>>();
+    *dns = Some(dns.unwrap_or_default() + started.elapsed());
+    if addrs.is_empty() {
+        bail!("{} has no address", host);
+    }
+    Ok(addrs)
+}
+
+// a connection to the first address of the host accepting one.
+async fn tcp(host: &str, port: u16, dns: &mut Option<Duration>) -> Result<(TcpStream, SocketAddr)> {
+    let mut error = None;
+    for addr in lookup(host, port, dns).await? {
+        match TcpStream::connect(addr).await {
+            Ok(stream) => {
+                stream.set_nodelay(true)?;
+                return Ok((stream, addr));
+            }
+            Err(e) => error = Some(e),
+        }
+    }
+    Err(anyhow!(
+        "connecting to {}:{} failed: {}",
+        host,
+        port,
+        error.map(|e| e.to_string()).unwrap_or_default()
+    ))
+}
+
+// open a tunnel to the host through an http proxy.
+async fn tunnel(
+    io: &mut dyn Io,
+    host: &str,
+    port: u16,
+    authorization: Option<String>,
+) -> Result<()> {
+    let mut request = format!(
+        "CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n",
+        host = host,
+        port = port
+    );
+    if let Some(authorization) = authorization {
+        request.push_str(&format!("Proxy-Authorization: {}\r\n", authorization));
+    }
+    request.push_str("\r\n");
+    io.write_all(request.as_bytes()).await?;
+    // nothing is sent through the tunnel before it is open, so the proxy sends
+    // nothing after its response
+    let mut response = Vec::new();
+    let mut buf = [0; 1024];
+    while !response.windows(4).any(|window| window == b"\r\n\r\n") {
+        if response.len() > MAX_TUNNEL_RESPONSE {
+            bail!("the response of the proxy to CONNECT is too large");
+        }
+        let n = io.read(&mut buf).await?;
+        if n == 0 {
+            bail!("the proxy closed the connection");
+        }
+        response.extend_from_slice(&buf[..n]);
+    }
+    let response = String::from_utf8_lossy(&response);
+    let status = response.lines().next().unwrap_or_default();
+    match status.split_whitespace().nth(1) {
+        Some("200") => Ok(()),
+        _ => bail!(
+            "the proxy refused to connect to {}:{}: {}",
+            host,
+            port,
+            status
+        ),
+    }
+}
+
+// connect to the target through a socks5 proxy.
+async fn socks<'t>(
+    io: Box<dyn Io>,
+    target: impl IntoTargetAddr<'t>,
+    credentials: Option<&(String, String)>,
+) -> Result<Box<dyn Io>> {
+    Ok(match credentials {
+        Some((username, password)) => Box::new(
+            Socks5Stream::connect_with_password_and_socket(io, target, username, password).await?,
+        ),
+        None => Box::new(Socks5Stream::connect_with_socket(io, target).await?),
+    })
+}
@@ -0,0 +1,440 @@
+use std::{cmp::Reverse, net::IpAddr};
+
+use anyhow::Result;
+use axum::http::header::{COOKIE, SET_COOKIE};
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone, Utc};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use sqlx_crud::Schema;
+use tokio::sync::Mutex;
+use url::Url;
+
+use crate::{
+    api::{error::Error, resp::ExpectRowsAffected},
+    entity::{
+        cookie_jar::{Cookie, CookieJar},
+        execution::RawHttpRequest,
+        id::Id,
+    },
+    service::{
+        assertion::text,
+        auth,
+        event::{Action, Event},
+    },
+    storage::{store, Storage},
+};
+
+// held while a jar is read and written back, so that executions sharing a jar
+// and edits of the jar do not lose the cookies of one another.
+static SAVING: Lazy<Mutex<()>> = Lazy::new(Default::default);
+
+// the jar an execution selected, it must be in the workspace.
+pub(crate) async fn jar(workspace_id: Id, cookie_jar_id: Option<Id>) -> Result<Option<CookieJar>> {
+    let Some(cookie_jar_id) = cookie_jar_id else {
+        return Ok(None);
+    };
+    Ok(Some(
+        store()
+            .by_id::<CookieJar>(cookie_jar_id)
+            .await?
+            .filter(|jar| jar.workspace_id == workspace_id)
+            .ok_or_else(|| Error::NotFound)?,
+    ))
+}
+
+// replace the name and cookies of a jar of the workspace, not while an
+// execution writes back to it.
+pub(crate) async fn update_jar(
+    workspace_id: Id,
+    id: Id,
+    name: String,
+    cookies: Vec<Cookie>,
+) -> Result<CookieJar, Error> {
+    let _saving = SAVING.lock().await;
+    let jar = store()
+        .by_id::<CookieJar>(id)
+        .await?
+        .filter(|jar| jar.workspace_id == workspace_id)
+        .ok_or_else(|| Error::NotFound)?;
+    let jar = CookieJar {
+        name,
+        cookies: serde_json::json!(cookies),
+        ..jar
+    };
+    store().update(jar.clone()).await?.expect(1)?;
+    Event::new(
+        CookieJar::table_name(),
+        Action::Updated,
+        jar.id.to_string(),
+        &jar,
+    )
+    .in_workspace(workspace_id)
+    .publish();
+    Ok(jar)
+}
+
+// the values of a header of the JSON object of headers, a repeated header is
+// an array of its values.
+fn values(headers: &Value, name: &str) -> Vec<String> {
+    let value = headers
+        .as_object()
+        .and_then(|headers| headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)))
+        .map(|(_, v)| v);
+    match value {
+        Some(Value::Array(values)) => values.iter().map(text).collect(),
+        Some(value) => vec![text(value)],
+        None => Vec::new(),
+    }
+}
+
+fn is_ip(host: &str) -> bool {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .is_ok()
+}
+
+// whether the host is the domain or one of its subdomains.
+fn domain_match(host: &str, domain: &str) -> bool {
+    host == domain
+        || (!is_ip(host)
+            && host.len() > domain.len()
+            && host.ends_with(domain)
+            && host[..host.len() - domain.len()].ends_with('.'))
+}
+
+// whether the path is the one of the cookie or below it.
+fn path_match(path: &str, cookie_path: &str) -> bool {
+    path == cookie_path
+        || (path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || path[cookie_path.len()..].starts_with('/')))
+}
+
+// the directory of the path of the url, the path of a cookie set without one.
+fn default_path(url: &Url) -> String {
+    let path = url.path();
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(last) => path[..last].to_string(),
+    }
+}
+
+// the date of an `Expires` attribute, in the format of RFC 1123 or the older
+// one of RFC 850.
+fn expires(value: &str) -> Option<DateTime<Local>> {
+    if let Ok(date) = DateTime::parse_from_rfc2822(value) {
+        return Some(date.with_timezone(&Local));
+    }
+    ["%A, %d-%b-%y %H:%M:%S GMT", "%a, %d-%b-%Y %H:%M:%S GMT"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|date| Utc.from_utc_datetime(&date).with_timezone(&Local))
+}
+
+// the cookie a `Set-Cookie` header of a response to the url sets, none when
+// the header is invalid or sets the cookie for another domain.
+fn parse(set_cookie: &str, url: &Url, now: DateTime<Local>) -> Option<Cookie> {
+    let host = url.host_str()?.to_ascii_lowercase();
+    let mut attributes = set_cookie.split(';');
+    let (name, value) = attributes.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let mut cookie = Cookie {
+        name: name.to_string(),
+        value: value.trim().trim_matches('"').to_string(),
+        domain: host.clone(),
+        host_only: true,
+        path: default_path(url),
+        expires: None,
+        secure: false,
+        http_only: false,
+    };
+    // `Max-Age` takes precedence over `Expires`
+    let mut max_age = None;
+    for attribute in attributes {
+        let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+        let value = value.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "domain" => {
+                let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                if domain.is_empty() {
+                    continue;
+                }
+                // a single label such as `com` is taken for a public suffix,
+                // which only the host itself may set a cookie for
+                let suffix = !domain.contains('.');
+                if !domain_match(&host, &domain) || (suffix && domain != host) {
+                    tracing::warn!("ignoring cookie {} of {} set by {}", name, domain, host);
+                    return None;
+                }
+                if !suffix {
+                    cookie.host_only = false;
+                    cookie.domain = domain;
+                }
+            }
+            "path" if value.starts_with('/') => cookie.path = value.to_string(),
+            "expires" => cookie.expires = expires(value).or(cookie.expires),
+            "max-age" => {
+                max_age = value
+                    .parse::<i64>()
+                    .ok()
+                    .map(|seconds| now + Duration::seconds(seconds.max(0)))
+                    .or(max_age)
+            }
+            "secure" => cookie.secure = true,
+            "httponly" => cookie.http_only = true,
+            _ => {}
+        }
+    }
+    cookie.expires = max_age.or(cookie.expires);
+    // only https sets the cookies only sent over https
+    if cookie.secure && url.scheme() != "https" {
+        return None;
+    }
+    Some(cookie)
+}
+
+fn expired(cookie: &Cookie, now: DateTime<Local>) -> bool {
+    cookie.expires.is_some_and(|expires| expires <= now)
+}
+
+// the cookies the `Set-Cookie` headers of a response to the url set, in the
+// order of the headers.
+pub(crate) fn set_by(url: &Url, headers: &Value, now: DateTime<Local>) -> Vec<Cookie> {
+    values(headers, SET_COOKIE.as_str())
+        .iter()
+        .filter_map(|set_cookie| parse(set_cookie, url, now))
+        .collect()
+}
+
+// keep the cookies the `Set-Cookie` headers of a response to the url set, a
+// cookie replaces the one of the same name, domain and path and is removed
+// when it is already expired.
+pub(crate) fn collect(cookies: &mut Vec<Cookie>, url: &Url, headers: &Value) {
+    let now = Local::now();
+    for cookie in set_by(url, headers, now) {
+        cookies.retain(|other| {
+            other.name != cookie.name || other.domain != cookie.domain || other.path != cookie.path
+        });
+        if !expired(&cookie, now) {
+            cookies.push(cookie);
+        }
+    }
+    cookies.retain(|cookie| !expired(cookie, now));
+}
+
+// the `Cookie` header of the cookies sent to the url, the more specific paths
+// first.
+pub(crate) fn header(cookies: &[Cookie], url: &Url) -> Option<String> {
+    let host = url.host_str()?.to_ascii_lowercase();
+    let now = Local::now();
+    let mut matching = cookies
+        .iter()
+        .filter(|cookie| !expired(cookie, now))
+        .filter(|cookie| match cookie.host_only {
+            true => host == cookie.domain,
+            false => domain_match(&host, &cookie.domain),
+        })
+        .filter(|cookie| path_match(url.path(), &cookie.path))
+        .filter(|cookie| !cookie.secure || url.scheme() == "https")
+        .collect::<Vec<_>>();
+    if matching.is_empty() {
+        return None;
+    }
+    // the sort is stable, the cookies of a path stay in the order they were set
+    matching.sort_by_key(|cookie| Reverse(cookie.path.len()));
+    Some(
+        matching
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>()
+            .join("; "),
+    )
+}
+
+// the cookies of a jar while an execution sends its request and follows its
+// redirects.
+pub(crate) struct Session {
+    pub(crate) cookies: Vec<Cookie>,
+    // the `Cookie` header of the saved request, sent along with the cookies of
+    // the jar until a redirect to another host drops it
+    own: Option<String>,
+    // the urls and headers of the responses setting cookies, in order
+    set: Vec<(Url, Value)>,
+}
+
+impl Session {
+    pub(crate) fn new(jar: &CookieJar, request: &RawHttpRequest) -> Self {
+        let own = values(&request.headers, COOKIE.as_str());
+        let mut cookies = jar.cookies();
+        cookies.retain(|cookie| !expired(cookie, Local::now()));
+        Session {
+            cookies,
+            own: (!own.is_empty()).then(|| own.join("; ")),
+            set: Vec::new(),
+        }
+    }
+
+    // set the `Cookie` header of the request to its own cookies and those of
+    // the jar sent to its url.
+    pub(crate) fn apply(&mut self, request: &mut RawHttpRequest) -> Result<()> {
+        if values(&request.headers, COOKIE.as_str()).is_empty() {
+            self.own = None;
+        }
+        let url = Url::parse(&request.url)?;
+        let cookies = self
+            .own
+            .iter()
+            .cloned()
+            .chain(header(&self.cookies, &url))
+            .collect::<Vec<_>>();
+        if cookies.is_empty() {
+            if let Some(headers) = request.headers.as_object_mut() {
+                headers.retain(|k, _| !k.eq_ignore_ascii_case(COOKIE.as_str()));
+            }
+        } else {
+            auth::set_header(request, COOKIE.as_str(), cookies.join("; "));
+        }
+        Ok(())
+    }
+
+    // keep the cookies a response to the request sets.
+    pub(crate) fn collect(&mut self, request: &RawHttpRequest, headers: &Value) -> Result<()> {
+        if values(headers, SET_COOKIE.as_str()).is_empty() {
+            return Ok(());
+        }
+        let url = Url::parse(&request.url)?;
+        collect(&mut self.cookies, &url, headers);
+        self.set.push((url, headers.clone()));
+        Ok(())
+    }
+
+    // keep the cookies the responses set in the jar, over the cookies it has
+    // now rather than those it had when the session started.
+    pub(crate) async fn save(self, jar_id: Id) -> Result<()> {
+        if self.set.is_empty() {
+            return Ok(());
+        }
+        let _saving = SAVING.lock().await;
+        let Some(mut jar) = store().by_id::<CookieJar>(jar_id).await? else {
+            return Ok(());
+        };
+        let before = jar.cookies();
+        let mut cookies = before.clone();
+        for (url, headers) in self.set.iter() {
+            collect(&mut cookies, url, headers);
+        }
+        if cookies != before {
+            jar.cookies = serde_json::to_value(cookies)?;
+            store().update(jar).await?.expect(1)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn match_domains_and_paths() {
+        assert!(domain_match("example.com", "example.com"));
+        assert!(domain_match("a.b.example.com", "example.com"));
+        assert!(!domain_match("badexample.com", "example.com"));
+        assert!(!domain_match("example.com", "a.example.com"));
+        assert!(!domain_match("10.0.0.1", "0.0.1"));
+
+        assert!(path_match("/", "/"));
+        assert!(path_match("/docs", "/docs"));
+        assert!(path_match("/docs/a", "/docs"));
+        assert!(path_match("/docs/a", "/docs/"));
+        assert!(!path_match("/docsets", "/docs"));
+        assert!(!path_match("/", "/docs"));
+
+        assert_eq!(default_path(&url("http://a.test/docs/page")), "/docs");
+        assert_eq!(default_path(&url("http://a.test/page")), "/");
+    }
+
+    #[test]
+    fn parse_set_cookie() {
+        let now = Local::now();
+        let cookie = parse(
+            "sid=\"abc\"; Domain=.Example.com; Path=/api; Max-Age=60; \
+             Expires=Wed, 21 Oct 2015 07:28:00 GMT; Secure; HttpOnly",
+            &url("https://www.example.com/login"),
+            now,
+        )
+        .unwrap();
+        assert_eq!(cookie.name, "sid");
+        assert_eq!(cookie.value, "abc");
+        assert_eq!(cookie.domain, "example.com");
+        assert!(!cookie.host_only);
+        assert_eq!(cookie.path, "/api");
+        // max-age takes precedence over expires
+        assert_eq!(cookie.expires, Some(now + Duration::seconds(60)));
+        assert!(cookie.secure && cookie.http_only);
+
+        let cookie = parse("a=1; Path=relative", &url("http://a.test/x/y"), now).unwrap();
+        assert!(cookie.host_only);
+        assert_eq!(cookie.domain, "a.test");
+        assert_eq!(cookie.path, "/x");
+        assert_eq!(cookie.expires, None);
+
+        let cookie = parse(
+            "a=1; Expires=Sunday, 06-Nov-94 08:49:37 GMT",
+            &url("http://a.test/"),
+            now,
+        )
+        .unwrap();
+        assert!(expired(&cookie, now));
+
+        // another domain, a public suffix, plain http and no name
+        assert!(parse("a=1; Domain=other.test", &url("http://a.test/"), now).is_none());
+        assert!(parse("a=1; Domain=test", &url("http://a.test/"), now).is_none());
+        assert!(parse("a=1; Secure", &url("http://a.test/"), now).is_none());
+        assert!(parse("=1", &url("http://a.test/"), now).is_none());
+        assert!(parse("novalue", &url("http://a.test/"), now).is_none());
+    }
+
+    #[test]
+    fn collect_and_send() {
+        let mut cookies = Vec::new();
+        let headers = json!({
+            "set-cookie": [
+                "a=1; Path=/",
+                "b=2; Path=/docs; Domain=example.com",
+                "c=3; Secure",
+                "gone=1; Max-Age=0"
+            ]
+        });
+        collect(&mut cookies, &url("https://example.com/login"), &headers);
+        assert_eq!(cookies.len(), 3);
+        // a cookie replaces the one of the same name, domain and path
+        let headers = json!({ "Set-Cookie": "a=4; Path=/" });
+        collect(&mut cookies, &url("https://example.com/"), &headers);
+        assert_eq!(cookies.len(), 3);
+
+        assert_eq!(
+            header(&cookies, &url("https://example.com/docs/a")).as_deref(),
+            Some("b=2; c=3; a=4")
+        );
+        assert_eq!(
+            header(&cookies, &url("http://www.example.com/docs")).as_deref(),
+            Some("b=2")
+        );
+        assert_eq!(header(&cookies, &url("http://other.test/")), None);
+
+        // an expired cookie is dropped
+        let headers = json!({ "set-cookie": "b=; Path=/docs; Domain=example.com; Max-Age=0" });
+        collect(&mut cookies, &url("https://example.com/"), &headers);
+        assert_eq!(cookies.len(), 2);
+    }
+}
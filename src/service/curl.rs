@@ -0,0 +1,495 @@
+use std::collections::VecDeque;
+
+use anyhow::{anyhow, bail, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::{
+    entity::{
+        body::{BodyKind, Part},
+        client::{ClientOptions, Redirect},
+        execution::RawHttpRequest,
+        id::Id,
+        request::Request,
+    },
+    service::{assertion::text, body, import},
+};
+
+// options taking a value which do not change the request, the value is skipped.
+const IGNORED_WITH_VALUE: &[&str] = &[
+    "-o",
+    "--output",
+    "-w",
+    "--write-out",
+    "--retry",
+    "-c",
+    "--cookie-jar",
+    // files of the machine of the command, which the server does not read
+    "-E",
+    "--cert",
+    "--key",
+    "--cacert",
+    "-r",
+    "--range",
+];
+
+// options without a value which do not change the request.
+const IGNORED: &[&str] = &[
+    "-s",
+    "--silent",
+    "-S",
+    "--show-error",
+    "-v",
+    "--verbose",
+    "-i",
+    "--include",
+    "-f",
+    "--fail",
+    "-N",
+    "--no-buffer",
+    "--http1.1",
+    "--http2",
+    "--globoff",
+    "-g",
+    // the client does not decompress, asking for a compressed response would
+    // leave the body unreadable
+    "--compressed",
+];
+
+// the short options taking a value, which may be attached as in `-XPOST`.
+const SHORT_WITH_VALUE: &str = "AbcdEeFHmoruwXx";
+
+#[derive(Debug, Default)]
+struct Curl {
+    method: Option<String>,
+    url: Option<String>,
+    headers: Vec<(String, String)>,
+    data: Vec<String>,
+    // `-F`, the fields of a multipart body
+    parts: Vec<Part>,
+    // `-G`, the data goes to the query
+    get: bool,
+    head: bool,
+    // the options changing how the request is sent, such as `-k`
+    options: ClientOptions,
+}
+
+impl Curl {
+    fn has_header(&self, name: &str) -> bool {
+        self.headers
+            .iter()
+            .any(|(k, _)| k.eq_ignore_ascii_case(name))
+    }
+
+    fn default_header(&mut self, name: &str, value: &str) {
+        if !self.has_header(name) {
+            self.headers.push((name.to_string(), value.to_string()));
+        }
+    }
+}
+
+// parse a curl command line into a request, the url is split into the host,
+// path and query of the request.
+//
+// the options changing the request are supported, the ones which only change
+// how curl runs, such as `-s` or `-o`, are ignored and any other option is an
+// error.
+pub(crate) fn parse(command: &str) -> Result<Request> {
+    // lines continued with a backslash
+    let command = command.replace("\\\r\n", " ").replace("\\\n", " ");
+    let mut words = shell_words::split(&command)?
+        .into_iter()
+        .collect::<VecDeque<_>>();
+    if words.front().map(String::as_str) == Some("curl") {
+        words.pop_front();
+    }
+    let mut curl = Curl::default();
+    while let Some(word) = words.pop_front() {
+        let (option, attached) = match split_short(&word) {
+            Split::Combined(options) => {
+                for option in options.into_iter().rev() {
+                    words.push_front(option);
+                }
+                continue;
+            }
+            Split::Attached(option, value) => (option, Some(value)),
+            Split::Single => (word.clone(), None),
+        };
+        let mut value = || match attached.clone() {
+            Some(value) => Ok(value),
+            None => words
+                .pop_front()
+                .ok_or_else(|| anyhow!("option {} requires a value", option)),
+        };
+        match option.as_str() {
+            "-X" | "--request" => curl.method = Some(value()?.to_uppercase()),
+            "--url" => curl.url = Some(value()?),
+            "-H" | "--header" => {
+                let header = value()?;
+                let (name, value) = header
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("invalid header: {}", header))?;
+                curl.headers
+                    .push((name.trim().to_string(), value.trim().to_string()));
+            }
+            "-d" | "--data" | "--data-ascii" | "--data-binary" => {
+                let data = value()?;
+                if data.starts_with('@') {
+                    bail!("reading the data from a file is not supported: {}", data);
+                }
+                curl.data.push(data);
+            }
+            "--data-raw" => curl.data.push(value()?),
+            "-F" | "--form" | "--form-string" => {
+                let field = value()?;
+                let (name, value) = field
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("invalid form field: {}", field))?;
+                if option != "--form-string" && (value.starts_with('@') || value.starts_with('<')) {
+                    bail!(
+                        "reading a form field from a file is not supported: {}",
+                        field
+                    );
+                }
+                curl.parts.push(Part {
+                    name: name.to_string(),
+                    value: Some(value.to_string()),
+                    filename: None,
+                    content_type: None,
+                    data: None,
+                });
+            }
+            "--json" => {
+                curl.data.push(value()?);
+                curl.default_header("Content-Type", "application/json");
+                curl.default_header("Accept", "application/json");
+            }
+            "-u" | "--user" => {
+                let user = value()?;
+                let user = if user.contains(':') {
+                    user
+                } else {
+                    format!("{}:", user)
+                };
+                let authorization = format!("Basic {}", STANDARD.encode(user));
+                curl.headers
+                    .push(("Authorization".to_string(), authorization));
+            }
+            "-A" | "--user-agent" => curl.headers.push(("User-Agent".to_string(), value()?)),
+            "-e" | "--referer" => curl.headers.push(("Referer".to_string(), value()?)),
+            "-b" | "--cookie" => {
+                let cookie = value()?;
+                if !cookie.contains('=') {
+                    bail!(
+                        "reading the cookies from a file is not supported: {}",
+                        cookie
+                    );
+                }
+                curl.headers.push(("Cookie".to_string(), cookie));
+            }
+            "-m" | "--max-time" => curl.options.timeout_ms = Some(millis(&value()?)?),
+            "--connect-timeout" => curl.options.connect_timeout_ms = Some(millis(&value()?)?),
+            "-L" | "--location" => {
+                curl.options.redirect.get_or_insert(Redirect::Follow);
+            }
+            "--max-redirs" => {
+                let max = value()?;
+                // curl takes -1 for no limit
+                curl.options.redirect = match max.parse::<i64>()? {
+                    max if max < 0 => Some(Redirect::Follow),
+                    max => Some(Redirect::Max(max as usize)),
+                };
+            }
+            "-k" | "--insecure" => curl.options.insecure = Some(true),
+            "-x" | "--proxy" => curl.options.proxy = Some(value()?),
+            "-G" | "--get" => curl.get = true,
+            "-I" | "--head" => curl.head = true,
+            option if IGNORED.contains(&option) => {}
+            option if IGNORED_WITH_VALUE.contains(&option) => {
+                value()?;
+            }
+            option if option.starts_with('-') && option.len() > 1 => {
+                bail!("unsupported curl option: {}", option)
+            }
+            _ => curl.url = Some(word),
+        }
+    }
+    into_request(curl)
+}
+
+// the seconds curl takes as milliseconds, they may have a fraction.
+fn millis(seconds: &str) -> Result<u64> {
+    let seconds = seconds
+        .parse::<f64>()
+        .map_err(|_| anyhow!("invalid number of seconds: {}", seconds))?;
+    Ok((seconds * 1000.0) as u64)
+}
+
+enum Split {
+    // combined short options such as `-sSL`, one word per option
+    Combined(Vec<String>),
+    // a short option and its value, such as `-XPOST`
+    Attached(String, String),
+    Single,
+}
+
+fn split_short(word: &str) -> Split {
+    if word.starts_with("--") || !word.starts_with('-') || word.len() <= 2 {
+        return Split::Single;
+    }
+    let (option, rest) = word.split_at(2);
+    if SHORT_WITH_VALUE.contains(&option[1..]) {
+        return Split::Attached(option.to_string(), rest.to_string());
+    }
+    let mut options = vec![option.to_string()];
+    // the first option taking a value takes the rest of the word with it
+    for (i, c) in rest.char_indices() {
+        if SHORT_WITH_VALUE.contains(c) {
+            options.push(format!("-{}", &rest[i..]));
+            break;
+        }
+        options.push(format!("-{}", c));
+    }
+    Split::Combined(options)
+}
+
+fn into_request(mut curl: Curl) -> Result<Request> {
+    let url = curl
+        .url
+        .take()
+        .ok_or_else(|| anyhow!("no url in the curl command"))?;
+    // curl defaults to http without a scheme
+    let url = if url.contains("://") {
+        url
+    } else {
+        format!("http://{}", url)
+    };
+    let mut url = Url::parse(&url)?;
+    let data = curl.data.join("&");
+    let mut body = None;
+    let mut body_kind = BodyKind::Json;
+    if curl.get {
+        if !data.is_empty() {
+            let query = match url.query() {
+                Some(query) => format!("{}&{}", query, data),
+                None => data,
+            };
+            url.set_query(Some(&query));
+        }
+    } else if !curl.parts.is_empty() {
+        body_kind = BodyKind::Multipart;
+        body = Some(serde_json::to_value(&curl.parts)?);
+    } else if !curl.data.is_empty() {
+        curl.default_header("Content-Type", BodyKind::Form.content_type());
+        let content_type = curl
+            .headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+            .map(|(_, v)| v.to_lowercase())
+            .unwrap_or_default();
+        // a JSON body is kept as JSON, anything else as text
+        (body_kind, body) = match serde_json::from_str(&data) {
+            Ok(json) if content_type.contains("json") => (BodyKind::Json, Some(json)),
+            _ if content_type.starts_with(BodyKind::Form.content_type()) => {
+                (BodyKind::Form, Some(Value::String(data)))
+            }
+            _ => (BodyKind::Text, Some(Value::String(data))),
+        };
+    }
+    let method = match curl.method {
+        Some(method) => method,
+        None if curl.head => "HEAD".to_string(),
+        None if body.is_some() => "POST".to_string(),
+        None => "GET".to_string(),
+    };
+    let (host, path, query) = import::split_url(&url);
+    let headers = curl
+        .headers
+        .into_iter()
+        .map(|(k, v)| (k, Value::String(v)))
+        .collect::<Map<_, _>>();
+    Ok(Request {
+        id: Id::new(),
+        name: format!("{} {}", method, path),
+        method,
+        path,
+        query,
+        host,
+        headers: Value::Object(headers),
+        body_kind,
+        body,
+        assertions: Value::Array(Vec::new()),
+        extractors: Value::Array(Vec::new()),
+        client_options: serde_json::json!(curl.options),
+        ..Default::default()
+    })
+}
+
+// render the request as it is sent as a curl command, every argument is quoted
+// for a POSIX shell.
+//
+// the files of a multipart body are referred to by their names as their
+// content can not be part of the command, a binary body is piped to curl.
+pub(crate) fn render(request: &RawHttpRequest) -> Result<String> {
+    let mut args = vec!["curl".to_string()];
+    let has_body = request.body.is_some();
+    match request.method.as_str() {
+        "GET" if !has_body => {}
+        "HEAD" if !has_body => args.push("--head".to_string()),
+        method => args.push(format!("-X {}", shell_words::quote(method))),
+    }
+    args.push(shell_words::quote(&request.url).into_owned());
+    let multipart = request.body.is_some() && request.body_kind == BodyKind::Multipart;
+    let mut has_content_type = false;
+    if let Some(headers) = request.headers.as_object() {
+        for (name, value) in headers {
+            let content_type = name.eq_ignore_ascii_case("content-type");
+            // curl sets the content type of multipart with its boundary
+            if content_type && multipart {
+                continue;
+            }
+            has_content_type |= content_type;
+            let header = format!("{}: {}", name, text(value));
+            args.push(format!("-H {}", shell_words::quote(&header)));
+        }
+    }
+    let mut pipe = None;
+    if let Some(body) = &request.body {
+        let kind = request.body_kind;
+        if kind != BodyKind::Multipart && !has_content_type {
+            let header = format!("Content-Type: {}", kind.content_type());
+            args.push(format!("-H {}", shell_words::quote(&header)));
+        }
+        match kind {
+            BodyKind::Multipart => {
+                for part in body::parts(body)? {
+                    let arg = match (&part.data, &part.filename) {
+                        (Some(_), filename) => {
+                            let mut field = format!(
+                                "{}=@{}",
+                                part.name,
+                                filename.as_deref().unwrap_or(&part.name)
+                            );
+                            if let Some(content_type) = &part.content_type {
+                                field.push_str(&format!(";type={}", content_type));
+                            }
+                            format!("-F {}", shell_words::quote(&field))
+                        }
+                        (None, _) => {
+                            let field = format!(
+                                "{}={}",
+                                part.name,
+                                part.value.as_deref().unwrap_or_default()
+                            );
+                            format!("--form-string {}", shell_words::quote(&field))
+                        }
+                    };
+                    args.push(arg);
+                }
+            }
+            BodyKind::Binary => {
+                pipe = Some(format!(
+                    "printf %s {} | base64 -d | ",
+                    shell_words::quote(&text(body))
+                ));
+                args.push("--data-binary @-".to_string());
+            }
+            kind => {
+                let data = body::to_text(kind, body)?;
+                args.push(format!("--data-raw {}", shell_words::quote(&data)));
+            }
+        }
+    }
+    Ok(format!(
+        "{}{}",
+        pipe.unwrap_or_default(),
+        args.join(" \\\n  ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn split_combined_and_attached_options() {
+        assert!(matches!(split_short("--header"), Split::Single));
+        assert!(matches!(split_short("-H"), Split::Single));
+        assert!(matches!(
+            split_short("-XPOST"),
+            Split::Attached(option, value) if option == "-X" && value == "POST"
+        ));
+        assert!(matches!(
+            split_short("-sSL"),
+            Split::Combined(options) if options == ["-s", "-S", "-L"]
+        ));
+        // the option taking a value keeps the rest of the word
+        assert!(matches!(
+            split_short("-sXPUT"),
+            Split::Combined(options) if options == ["-s", "-XPUT"]
+        ));
+    }
+
+    #[test]
+    fn parse_a_command() {
+        let request = parse(
+            "curl -sSL -XPUT 'https://example.com:8443/users?page=1&tag=a' \\\n  \
+             -H 'Content-Type: application/json' -H \"X-Id: 7\" \\\n  \
+             --data-raw '{\"name\": \"x\"}' --max-time 1.5 -k --compressed",
+        )
+        .unwrap();
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.host, "https://example.com:8443");
+        assert_eq!(request.path, "/users");
+        assert_eq!(request.query, json!({ "page": "1", "tag": "a" }));
+        assert_eq!(
+            request.headers,
+            json!({ "Content-Type": "application/json", "X-Id": "7" })
+        );
+        assert_eq!(request.body_kind, BodyKind::Json);
+        assert_eq!(request.body, Some(json!({ "name": "x" })));
+        assert_eq!(
+            request.client_options,
+            json!({ "timeout_ms": 1500, "redirect": "follow", "insecure": true })
+        );
+    }
+
+    #[test]
+    fn parse_data_forms_and_queries() {
+        let request = parse("curl example.com -d a=1 -d b=2").unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.host, "http://example.com");
+        assert_eq!(request.body_kind, BodyKind::Form);
+        assert_eq!(request.body, Some(json!("a=1&b=2")));
+
+        let request = parse("curl -G 'example.com/?tag=a' -d tag=b -d page=2").unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.body, None);
+        assert_eq!(request.query, json!({ "tag": ["a", "b"], "page": "2" }));
+
+        let request = parse("curl example.com -F name=x --form-string 'note=@me'").unwrap();
+        assert_eq!(request.body_kind, BodyKind::Multipart);
+        let parts = body::parts(request.body.as_ref().unwrap()).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[1].value.as_deref(), Some("@me"));
+
+        let request = parse("curl -u user example.com").unwrap();
+        assert_eq!(
+            request.headers,
+            json!({ "Authorization": "Basic dXNlcjo=" })
+        );
+    }
+
+    #[test]
+    fn reject_what_the_server_can_not_do() {
+        assert!(parse("curl -d @body.json example.com").is_err());
+        assert!(parse("curl -F file=@a.png example.com").is_err());
+        assert!(parse("curl -b cookies.txt example.com").is_err());
+        assert!(parse("curl --unknown example.com").is_err());
+        assert!(parse("curl -H").is_err());
+        assert!(parse("curl -s").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use chrono::{DateTime, Local};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx_crud::Schema;
+use tokio::sync::broadcast;
+
+use crate::entity::{execution::Execution, id::Id, request::Request};
+
+// how many events are kept for the backfill of new subscribers.
+pub(crate) const HISTORY_SIZE: usize = 100;
+// how many events a slow subscriber may fall behind before losing some.
+const CHANNEL_CAPACITY: usize = 256;
+
+static HUB: Lazy<Hub> = Lazy::new(|| Hub {
+    sequence: AtomicU64::new(0),
+    sender: broadcast::channel(CHANNEL_CAPACITY).0,
+    history: Mutex::new(VecDeque::with_capacity(HISTORY_SIZE)),
+});
+
+struct Hub {
+    sequence: AtomicU64,
+    sender: broadcast::Sender<Event>,
+    history: Mutex<VecDeque<Event>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Action {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Event {
+    pub(crate) sequence: u64,
+    // the workspace of the entity, none for the entities of no workspace such
+    // as bins and mocks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) workspace_id: Option<Id>,
+    pub(crate) resource: &'static str,
+    pub(crate) action: Action,
+    pub(crate) id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) request_id: Option<String>,
+    pub(crate) time: DateTime<Local>,
+    pub(crate) data: Value,
+}
+
+impl Event {
+    pub(crate) fn new<T: Serialize>(
+        resource: &'static str,
+        action: Action,
+        id: String,
+        data: &T,
+    ) -> Self {
+        Self {
+            sequence: 0,
+            workspace_id: None,
+            resource,
+            action,
+            id,
+            request_id: None,
+            time: Local::now(),
+            data: serde_json::to_value(data).unwrap_or(Value::Null),
+        }
+    }
+
+    pub(crate) fn execution(execution: &Execution) -> Self {
+        Self {
+            request_id: Some(execution.request_id.to_string()),
+            ..Self::new(
+                Execution::table_name(),
+                Action::Created,
+                execution.id.to_string(),
+                execution,
+            )
+        }
+        .in_workspace(execution.workspace_id)
+    }
+
+    // scopes the event to a workspace, only its subscribers will see it.
+    pub(crate) fn in_workspace(mut self, workspace_id: Id) -> Self {
+        self.workspace_id = Some(workspace_id);
+        self
+    }
+
+    // the event name for server-sent events, e.g. `requests.created`.
+    pub(crate) fn name(&self) -> String {
+        let action = match self.action {
+            Action::Created => "created",
+            Action::Updated => "updated",
+            Action::Deleted => "deleted",
+        };
+        format!("{}.{}", self.resource, action)
+    }
+
+    // whether a subscriber of the workspace may see the event, the events of
+    // other workspaces are never sent to it.
+    pub(crate) fn visible_in(&self, workspace_id: Id) -> bool {
+        self.workspace_id.is_none() || self.workspace_id == Some(workspace_id)
+    }
+
+    // whether the event is about the saved request itself or one of its
+    // executions.
+    pub(crate) fn concerns(&self, request_id: &str) -> bool {
+        self.request_id.as_deref() == Some(request_id)
+            || (self.resource == Request::table_name() && self.id == request_id)
+    }
+
+    pub(crate) fn publish(mut self) {
+        // hold the history lock while sending so that subscribers never see an
+        // event both in their backfill and on the channel.
+        let mut history = HUB.history.lock().unwrap();
+        self.sequence = HUB.sequence.fetch_add(1, Ordering::Relaxed) + 1;
+        if history.len() == HISTORY_SIZE {
+            history.pop_front();
+        }
+        history.push_back(self.clone());
+        tracing::debug!("publish event {} {}", self.name(), self.id);
+        // there is nobody listening when sending fails, which is fine.
+        let _ = HUB.sender.send(self);
+    }
+}
+
+// subscribe to events published from now on, together with the last
+// `backfill` events published before which pass the filter. the receiver gets
+// every event, the subscriber applies the same filter to them.
+pub(crate) fn subscribe(
+    backfill: usize,
+    filter: impl Fn(&Event) -> bool,
+) -> (Vec<Event>, broadcast::Receiver<Event>) {
+    let history = HUB.history.lock().unwrap();
+    let receiver = HUB.sender.subscribe();
+    let mut events = history
+        .iter()
+        .rev()
+        .filter(|event| filter(event))
+        .take(backfill)
+        .cloned()
+        .collect::<Vec<_>>();
+    events.reverse();
+    (events, receiver)
+}
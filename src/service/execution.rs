@@ -1,242 +1,565 @@
-use std::str::FromStr;
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 use anyhow::Result;
-use axum::http::{HeaderName, HeaderValue};
+use axum::http::{
+    header::{
+        AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, LOCATION, PROXY_AUTHORIZATION,
+        WWW_AUTHENTICATE,
+    },
+    HeaderMap, HeaderName, HeaderValue, StatusCode,
+};
 use chrono::Local;
-use hyper::Method;
-use reqwest::Url;
-use serde_json::Value;
-use sqlx::types::uuid::fmt::Hyphenated;
-use sqlx_crud::Crud;
-use uuid::Uuid;
+use hyper::{Body, Method, Response, Uri};
+use serde_json::{Map, Value};
+use sqlx_crud::Schema;
+use url::Url;
 
 use crate::{
     api::{error::Error, resp::ExpectRowsAffected},
-    db,
+    config::global_config,
     entity::{
-        execution::{Execution, RawHttpRequest, RawHttpResponse},
+        assertion::Assertion,
+        auth::Auth,
+        client::ClientOptions,
+        environment::Environment,
+        execution::{Execution, Hop, RawHttpRequest, RawHttpResponse, Timings},
+        extractor::Extractor,
+        id::Id,
         request::Request,
     },
+    service::{
+        assertion::{self, text},
+        auth, body,
+        client::Client,
+        connector::RemoteAddr,
+        cookie::{self, Session},
+        event::Event,
+        extractor, template,
+        timing::{self, Phases},
+        variable,
+    },
+    storage::{store, Filter, Storage},
 };
 
-async fn make_response(resp: reqwest::Response) -> Result<RawHttpResponse> {
-    let is_json = resp
-        .headers()
-        .get("Content-Type")
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .contains("application/json");
-    let headers = resp
+// the headers as a JSON object, repeated headers such as `set-cookie` are kept
+// as an array.
+pub(crate) fn headers_value(headers: &HeaderMap) -> Value {
+    let mut object = serde_json::Map::new();
+    for (k, v) in headers.iter() {
+        let value = Value::String(String::from_utf8_lossy(v.as_bytes()).into_owned());
+        match object.get_mut(k.as_str()) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(first) => *first = Value::Array(vec![first.take(), value]),
+            None => {
+                object.insert(k.to_string(), value);
+            }
+        }
+    }
+    Value::Object(object)
+}
+
+// the response with its body, read within the timeout of the request sent at
+// `sent`.
+async fn make_response(
+    client: &Client,
+    resp: Response<Body>,
+    deadline: Option<Instant>,
+) -> Result<RawHttpResponse> {
+    let headers = headers_value(resp.headers());
+    let declared = resp
         .headers()
-        .iter()
-        .map(|(k, v)| {
-            (
-                k.to_string(),
-                Value::String(v.to_str().unwrap().to_string()),
-            )
-        })
-        .collect::<Value>();
+        .get(CONTENT_TYPE)
+        .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned());
     let version = format!("{:?}", resp.version());
-    let status_code = resp.status().as_u16();
-    let status_message = resp.status().canonical_reason().unwrap().to_string();
+    let status_code = resp.status().as_u16().into();
+    let status_message = resp
+        .status()
+        .canonical_reason()
+        .unwrap_or_default()
+        .to_string();
+    let (raw_body, truncated) = client.read(resp.into_body(), deadline).await?;
+    if truncated {
+        tracing::warn!(
+            "the body of the response is cut at {} bytes",
+            raw_body.len()
+        );
+    }
+    let content_type = body::content_type(declared.as_deref(), &raw_body);
     Ok(RawHttpResponse {
-        id: 0,
+        id: Id::new(),
         version,
         status_code,
         status_message,
         headers,
-        body: if is_json {
-            let json = resp.json().await?;
-            json
-        } else {
-            let text = resp.text().await?;
-            Value::String(text)
-        },
+        size: raw_body.len() as i64,
+        body: body::decode(&content_type, &raw_body),
+        content_type,
+        raw_body,
+        truncated,
     })
 }
 
-async fn make_request(request_id: Hyphenated) -> Result<RawHttpRequest> {
-    let request = Request::by_id(db::db_pool(), request_id)
+// the variables a request of the workspace is rendered with.
+pub(crate) async fn variables(
+    workspace_id: Id,
+    environment_id: Option<Id>,
+) -> Result<HashMap<String, String>> {
+    // the variables of the workspace, e.g. a token extracted by an earlier
+    // execution, are overridden by those of the environment chosen
+    let mut variables = variable::variables(workspace_id).await?;
+    if let Some(environment_id) = environment_id {
+        let environment = store()
+            .by_id::<Environment>(environment_id)
+            .await?
+            .filter(|environment| environment.workspace_id == workspace_id)
+            .ok_or_else(|| Error::NotFound)?;
+        variables.extend(environment.variables());
+    }
+    Ok(variables)
+}
+
+// the saved request, the request sent for it and its auth, which is applied
+// once the client is built.
+async fn make_request(
+    workspace_id: Id,
+    request_id: Id,
+    environment_id: Option<Id>,
+) -> Result<(Request, RawHttpRequest, Option<Auth>)> {
+    let request = store()
+        .by_id::<Request>(request_id)
         .await?
+        .filter(|request| request.workspace_id == workspace_id)
         .ok_or_else(|| Error::NotFound)?;
-    let mut request = prepare_request(&request)?;
-    let request_id = request
-        .clone()
-        .create(db::db_pool())
-        .await
-        .map_err(|e| Error::CreateFailed(e.to_string()))?
-        .last_insert_id();
-    request.id = request_id;
-    Ok(request)
+    let variables = variables(workspace_id, environment_id).await?;
+    let raw = prepare_request(&request, &variables)?;
+    let auth = auth::parse(request.auth.as_ref(), &variables)
+        .map_err(|e| Error::BadRequest(e.to_string()))?;
+    Ok((request, raw, auth))
+}
+
+fn make_http_request(request: &RawHttpRequest) -> Result<hyper::Request<Body>> {
+    let mut headers = HeaderMap::new();
+    if let Some(request_headers) = request.headers.as_object() {
+        for (name, value) in request_headers {
+            let name = HeaderName::from_str(name)?;
+            // a repeated header is sent once per value
+            let values = match value {
+                Value::Array(values) => values.iter().map(text).collect(),
+                other => vec![text(other)],
+            };
+            for value in values {
+                headers.append(name.clone(), HeaderValue::from_str(&value)?);
+            }
+        }
+    }
+    let body = match &request.body {
+        Some(body) => {
+            // the content type is the one `prepare_request` set
+            let declared = headers
+                .get(CONTENT_TYPE)
+                .and_then(|declared| declared.to_str().ok());
+            let (bytes, content_type) = body::encode(request.body_kind, body, declared)?;
+            headers.insert(CONTENT_TYPE, HeaderValue::from_str(&content_type)?);
+            Body::from(bytes)
+        }
+        None => Body::empty(),
+    };
+    let mut http = hyper::Request::new(body);
+    *http.method_mut() = Method::from_str(&request.method)?;
+    *http.uri_mut() = Uri::from_str(Url::parse(&request.url)?.as_str())?;
+    *http.headers_mut() = headers;
+    Ok(http)
+}
+
+// the options of the saved request over those of the configuration.
+fn client_options(request: &Request) -> Result<ClientOptions> {
+    let options = serde_json::from_value::<Option<ClientOptions>>(request.client_options.clone())
+        .map_err(|e| Error::BadRequest(format!("invalid client options: {}", e)))?;
+    let options = options.unwrap_or_default().or(&global_config().client);
+    options
+        .check()
+        .map_err(|e| Error::BadRequest(format!("invalid client options: {}", e)))?;
+    Ok(options)
+}
+
+// the request sent to follow a redirect to `location`.
+//
+// as browsers do, 301, 302 and 303 are followed with a GET without the body,
+// and the credentials are not sent to another host.
+pub(crate) fn redirected(
+    request: &RawHttpRequest,
+    status: StatusCode,
+    location: &str,
+) -> Result<RawHttpRequest> {
+    let from = Url::parse(&request.url)?;
+    let to = from.join(location)?;
+    let mut dropped = Vec::new();
+    let mut next = request.clone();
+    if status != StatusCode::TEMPORARY_REDIRECT
+        && status != StatusCode::PERMANENT_REDIRECT
+        && request.method != "HEAD"
+    {
+        next.method = "GET".to_string();
+        next.body = None;
+        dropped.extend([CONTENT_TYPE, CONTENT_LENGTH]);
+    }
+    if from.host_str() != to.host_str()
+        || from.port_or_known_default() != to.port_or_known_default()
+    {
+        dropped.extend([AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION]);
+    }
+    if let Some(headers) = next.headers.as_object_mut() {
+        headers.retain(|name, _| {
+            !dropped
+                .iter()
+                .any(|dropped| name.eq_ignore_ascii_case(dropped.as_str()))
+        });
+    }
+    next.id = Id::new();
+    next.url = to.to_string();
+    Ok(next)
 }
 
-async fn make_request_builder(request: &RawHttpRequest) -> Result<reqwest::RequestBuilder> {
-    let builder = reqwest::Client::new()
-        .request(
-            Method::from_str(&request.method).unwrap(),
-            Url::parse(&request.url).unwrap(),
-        )
-        .headers(
-            request
-                .headers
-                .as_object()
-                .unwrap()
+// send the request and follow its redirects up to `max_redirects`, the
+// response of the last redirect is returned when there are more. the redirects
+// followed are returned with the response, when it was sent and the phases of
+// the connection it was sent over, if it opened one.
+//
+// the first 401 of a hop is answered once when the auth is a digest. with a
+// session, every hop sends the cookies of the jar and keeps those it is set.
+// all the hops are sent within the deadline of the execution.
+async fn send(
+    client: &Client,
+    auth: Option<&Auth>,
+    mut session: Option<&mut Session>,
+    request: &RawHttpRequest,
+    max_redirects: usize,
+    deadline: Option<Instant>,
+) -> Result<(Vec<Hop>, Response<Body>, Instant, Option<Phases>)> {
+    let mut request = request.clone();
+    let mut hops = Vec::new();
+    let mut challenged = false;
+    loop {
+        if let Some(session) = session.as_deref_mut() {
+            session.apply(&mut request)?;
+        }
+        // the phases of a connection opened for an earlier hop
+        client.take_phases();
+        let sent = Instant::now();
+        let request_time = Local::now();
+        let resp = client.send(make_http_request(&request)?, deadline).await?;
+        let response_time = Local::now();
+        let ttfb = sent.elapsed();
+        let phases = client.take_phases();
+        let headers = headers_value(resp.headers());
+        if let Some(session) = session.as_deref_mut() {
+            session.collect(&request, &headers)?;
+        }
+        let digest = auth.filter(|_| !challenged && resp.status() == StatusCode::UNAUTHORIZED);
+        if let Some(digest) = digest {
+            let answer = resp
+                .headers()
+                .get_all(WWW_AUTHENTICATE)
                 .iter()
-                .map(|(k, v)| {
-                    (
-                        HeaderName::from_str(&k.to_string()).unwrap(),
-                        HeaderValue::from_str(&v.to_string()).unwrap(),
-                    )
-                })
-                .collect(),
+                .filter_map(|challenge| challenge.to_str().ok())
+                .find_map(|challenge| auth::digest(digest, &request, challenge).transpose())
+                .transpose()?;
+            if let Some(answer) = answer {
+                challenged = true;
+                auth::set_header(&mut request, AUTHORIZATION.as_str(), answer);
+                continue;
+            }
+        }
+        challenged = false;
+        let location = resp
+            .headers()
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .filter(|_| resp.status().is_redirection());
+        let Some(location) = location else {
+            return Ok((hops, resp, sent, phases));
+        };
+        if hops.len() == max_redirects {
+            tracing::warn!(
+                "not following the redirect to {}, {} followed",
+                location,
+                hops.len()
+            );
+            return Ok((hops, resp, sent, phases));
+        }
+        tracing::info!(
+            "{} redirected to {} with {}",
+            request.url,
+            location,
+            resp.status()
         );
-    let builder = if request.body.is_none() {
-        builder
-    } else {
-        builder.json(&request.body.clone().unwrap())
-    };
-    Ok(builder)
+        let next = redirected(&request, resp.status(), location)?;
+        hops.push(Hop {
+            method: request.method,
+            url: request.url,
+            status_code: resp.status().as_u16().into(),
+            status_message: resp
+                .status()
+                .canonical_reason()
+                .unwrap_or_default()
+                .to_string(),
+            location: next.url.clone(),
+            headers,
+            request_time,
+            response_time,
+            timings: timings(phases, ttfb, Duration::ZERO),
+        });
+        request = next;
+    }
+}
+
+fn timings(phases: Option<Phases>, ttfb: Duration, download: Duration) -> Timings {
+    Timings {
+        dns_ms: phases.and_then(|phases| phases.dns).map(timing::millis),
+        connect_ms: phases.map(|phases| timing::millis(phases.connect)),
+        tls_ms: phases.and_then(|phases| phases.tls).map(timing::millis),
+        ttfb_ms: timing::millis(ttfb),
+        download_ms: timing::millis(download),
+    }
 }
 
-pub(crate) async fn execute_request(request_id: Hyphenated) -> Result<Execution> {
-    let request = make_request(request_id).await?;
-    let builder = make_request_builder(&request).await?;
+pub(crate) async fn execute_request(
+    workspace_id: Id,
+    request_id: Id,
+    environment_id: Option<Id>,
+    cookie_jar_id: Option<Id>,
+) -> Result<Execution> {
+    let (saved, mut request, auth) = make_request(workspace_id, request_id, environment_id).await?;
+    let jar = cookie::jar(workspace_id, cookie_jar_id).await?;
+    let options = client_options(&saved)?;
+    let client = Client::build(&options)?;
+    // the timeout is of the whole execution, the token of the auth included
+    let deadline = client.deadline();
+    if let Some(auth) = &auth {
+        auth::apply(&client, auth, &mut request, deadline).await?;
+    }
+    // the request is stored with the cookies of the jar it is sent with
+    let mut session = jar.as_ref().map(|jar| Session::new(jar, &request));
+    if let Some(session) = session.as_mut() {
+        session.apply(&mut request)?;
+    }
     let request_time = Local::now();
     tracing::info!("send request at {}", request_time);
-    let resp = builder.send().await?;
+    let (redirects, resp, sent, phases) = send(
+        &client,
+        auth.as_ref(),
+        session.as_mut(),
+        &request,
+        options.max_redirects(),
+        deadline,
+    )
+    .await?;
     let response_time = Local::now();
     tracing::info!("get response at {}", response_time);
-    let /* mut */ response = make_response(resp).await?;
-    let resp_id = response
-        .clone()
-        .create(db::db_pool())
-        .await
-        .map_err(|e| Error::CreateFailed(e.to_string()))?
-        .last_insert_id();
-    // response.id = resp_id;
+    let ttfb = sent.elapsed();
+    let remote_addr = resp
+        .extensions()
+        .get::<RemoteAddr>()
+        .map(|RemoteAddr(addr)| *addr);
+    let response = make_response(&client, resp, deadline).await?;
+    let timings = timings(phases, ttfb, sent.elapsed() - ttfb);
+    // the jar keeps the cookies set by the response and its redirects
+    if let (Some(jar), Some(session)) = (jar, session) {
+        session.save(jar.id).await?;
+    }
+    let assertions = serde_json::from_value::<Vec<Assertion>>(saved.assertions)
+        .map_err(|e| Error::BadRequest(format!("invalid assertions: {}", e)))?;
+    let latency_ms = (response_time - request_time).num_milliseconds();
+    let results = assertion::evaluate(&assertions, &response, latency_ms);
+    let extractors = serde_json::from_value::<Vec<Extractor>>(saved.extractors)
+        .map_err(|e| Error::BadRequest(format!("invalid extractors: {}", e)))?;
+    // the response is the one to the last redirect followed
+    let url = Url::parse(
+        redirects
+            .last()
+            .map_or(request.url.as_str(), |hop| hop.location.as_str()),
+    )?;
+    let extracted = extractor::extract(&extractors, &response, &url);
     let execution = Execution {
-        id: Uuid::new_v4().hyphenated(),
+        id: Id::new(),
+        workspace_id,
+        request_id,
+        environment_id,
+        cookie_jar_id,
         request: request.id,
         request_time,
         response_time,
-        response: resp_id,
+        response: response.id,
+        passed: results.iter().all(|result| result.passed),
+        assertions: serde_json::to_value(results)?,
+        extracted: serde_json::to_value(&extracted)?,
+        redirects: serde_json::to_value(redirects)?,
+        timings: serde_json::to_value(timings)?,
+        remote_addr: remote_addr.map(|addr| addr.to_string()),
     };
-    execution
-        .clone()
-        .create(db::db_pool())
-        .await?
-        .rows_affected()
-        .expect(1)?;
+    save(request, response, &execution).await?;
+    for (name, value) in extracted {
+        variable::set(workspace_id, name, value, Some(execution.id)).await?;
+    }
+    Event::execution(&execution).publish();
     Ok(execution)
-    // let request = reqwest::Request {
-    //     method: Method::from_str(&request.method).unwrap(),
-    //     url: Url::parse(&request.url).unwrap(),
-    //     headers: request
-    //         .headers
-    //         .as_object()
-    //         .unwrap()
-    //         .iter()
-    //         .map(|(k, v)| {
-    //             (
-    //                 HeaderName::from_str(&k.to_string()).unwrap(),
-    //                 HeaderValue::from_str(&v.to_string()).unwrap(),
-    //             )
-    //         })
-    //         .collect(),
-    //     body: match request.body {
-    //         Some(body) => Body::empty(),
-    //         None => None,
-    //     },
-    //     timeout: 10000,
-    //     version: hyper::Version::HTTP_11,
-    // };
-    // let request = reqwest::Request::new(
-    //     Method::from_str(&request.method).unwrap(),
-    //     Url::parse(&request.url).unwrap(),
-    // );
-    // let uri = request.uri().to_string();
-    // let headers = request.headers();
-    // let send_time = Local::now();
-    // let response = Client::new().request(request).await?;
-    // let respond_time = Local::now();
-    // let is_json = response
-    //     .headers()
-    //     .get("Content-Type")
-    //     .unwrap()
-    //     .to_str()
-    //     .unwrap()
-    //     .contains("application/json");
-    // let headers = response
-    //     .headers()
-    //     .iter()
-    //     .map(|(k, v)| {
-    //         (
-    //             k.to_string(),
-    //             Value::String(v.to_str().unwrap().to_string()),
-    //         )
-    //     })
-    //     .collect();
-    // let status = response.status().as_u16();
-    // let response = if is_json {
-    //     let body = hyper::body::to_bytes(response.into_body()).await?;
-    //     let resp = serde_json::from_slice(&body).unwrap();
-    //     Response {
-    //         id: Uuid::new_v4().hyphenated(),
-    //         status,
-    //         header: Value::Object(headers),
-    //         body: Some(resp),
-    //         message: None,
-    //         created_at: None,
-    //         updated_at: None,
-    //         deleted_at: None,
-    //     }
-    // } else {
-    //     let msg =
-    //         String::from_utf8(hyper::body::to_bytes(response.into_body()).await?.to_vec()).unwrap();
-    //     Response {
-    //         id: Uuid::new_v4().hyphenated(),
-    //         status,
-    //         header: Value::Object(headers),
-    //         body: None,
-    //         message: Some(msg),
-    //         created_at: None,
-    //         updated_at: None,
-    //         deleted_at: None,
-    //     }
-    // };
-    // response
-    //     .clone()
-    //     .create(db::db_pool())
-    //     .await
-    //     .map_err(|e| Error::CreateFailed(e.to_string()))?
-    //     .rows_affected()
-    //     .expect(1)?;
-    // Ok(response)
 }
 
-fn prepare_url(r: &Request) -> Result<String> {
-    let obj = r.query.as_object().unwrap();
-    let uri = if obj.is_empty() {
-        format!("{}{}", r.host, r.path)
-    } else {
-        let x = obj
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .collect::<Vec<_>>()
-            .join("&");
-        format!("{}{}?{}", r.host, r.path, x)
-    };
-    Ok(uri)
+// store the execution with the request it sent and the response, once it has
+// them all, so that a failed execution leaves nothing behind. whatever was
+// stored is deleted again when storing the rest fails.
+async fn save(
+    request: RawHttpRequest,
+    response: RawHttpResponse,
+    execution: &Execution,
+) -> Result<()> {
+    let mut stored = Vec::new();
+    let result = async {
+        store().create(request.clone()).await?.expect(1)?;
+        stored.push((RawHttpRequest::table_name(), request.id));
+        store().create(response.clone()).await?.expect(1)?;
+        stored.push((RawHttpResponse::table_name(), response.id));
+        store().create(execution.clone()).await?.expect(1)?;
+        Ok::<_, Error>(())
+    }
+    .await;
+    if let Err(e) = result {
+        let deletes = stored
+            .into_iter()
+            .map(|(table, id)| {
+                let mut filter = Filter::default();
+                filter.and_eq("id", id);
+                (table, filter)
+            })
+            .collect::<Vec<_>>();
+        store().delete_where(&deletes).await?;
+        return Err(Error::CreateFailed(e.to_string()).into());
+    }
+    Ok(())
 }
 
-fn prepare_request(request: &Request) -> Result<RawHttpRequest> {
-    Ok(RawHttpRequest {
-        id: 0,
+fn prepare_url(host: &str, path: &str, query: &Value) -> Result<String> {
+    let mut url = Url::parse(&format!("{}{}", host, path))?;
+    if let Some(query) = query.as_object().filter(|query| !query.is_empty()) {
+        let mut pairs = url.query_pairs_mut();
+        for (k, v) in query {
+            // a key given more than once keeps its values in an array
+            let values = match v {
+                Value::Array(values) => values.as_slice(),
+                other => std::slice::from_ref(other),
+            };
+            for v in values {
+                match v {
+                    Value::String(s) => pairs.append_pair(k, s),
+                    other => pairs.append_pair(k, &other.to_string()),
+                };
+            }
+        }
+    }
+    Ok(url.to_string())
+}
+
+// the request as it is sent, with the variables and generators in host, path,
+// query, headers and body rendered to concrete values.
+pub(crate) fn prepare_request(
+    request: &Request,
+    variables: &HashMap<String, String>,
+) -> Result<RawHttpRequest> {
+    let host = template::render(&request.host, variables)?;
+    let path = template::render(&request.path, variables)?;
+    let query = template::render_value(&request.query, variables)?;
+    let mut raw = RawHttpRequest {
+        id: Id::new(),
         method: request.method.clone(),
-        url: prepare_url(&request)?,
+        url: prepare_url(&host, &path, &query)?,
         version: "HTTP/1.1".to_string(),
-        headers: request.headers.clone(),
-        body: request.body.clone(),
-    })
+        headers: match template::render_value(&request.headers, variables)? {
+            Value::Null => Value::Object(Map::new()),
+            headers => headers,
+        },
+        body_kind: request.body_kind,
+        body: request
+            .body
+            .as_ref()
+            .map(|body| template::render_value(body, variables))
+            .transpose()?,
+    };
+    // the request is kept with the content type it is sent with, a multipart
+    // body with the boundary it is encoded with
+    if raw.body.is_some() {
+        let declared = body::declared_type(&raw.headers);
+        let content_type = body::sent_type(raw.body_kind, declared.as_deref());
+        auth::set_header(&mut raw, "Content-Type", content_type);
+    }
+    Ok(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::entity::body::BodyKind;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn keep_the_multipart_boundary_sent() {
+        let request = Request {
+            method: "POST".to_string(),
+            host: "https://{{host}}".to_string(),
+            path: "/upload".to_string(),
+            query: json!({ "tag": ["a", "{{tag}}"] }),
+            headers: json!({ "content-type": "multipart/form-data" }),
+            body_kind: BodyKind::Multipart,
+            body: Some(json!([{ "name": "note", "value": "{{tag}}" }])),
+            ..Default::default()
+        };
+        let variables = HashMap::from([
+            ("host".to_string(), "example.com".to_string()),
+            ("tag".to_string(), "b".to_string()),
+        ]);
+        let raw = prepare_request(&request, &variables).unwrap();
+        assert_eq!(raw.url, "https://example.com/upload?tag=a&tag=b");
+        let content_type = body::declared_type(&raw.headers).unwrap();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        assert_eq!(raw.headers.as_object().unwrap().len(), 1);
+
+        let http = make_http_request(&raw).unwrap();
+        assert_eq!(http.headers()[CONTENT_TYPE], content_type.as_str());
+        let bytes = hyper::body::to_bytes(http.into_body()).await.unwrap();
+        assert!(bytes.starts_with(format!("--{}\r\n", boundary).as_bytes()));
+        assert!(bytes.ends_with(format!("--{}--\r\n", boundary).as_bytes()));
+    }
+
+    #[test]
+    fn set_the_content_type_of_the_kind() {
+        let request = Request {
+            method: "POST".to_string(),
+            host: "http://example.com".to_string(),
+            path: "/".to_string(),
+            headers: Value::Null,
+            body_kind: BodyKind::Form,
+            body: Some(json!({ "a": "1" })),
+            ..Default::default()
+        };
+        let raw = prepare_request(&request, &HashMap::new()).unwrap();
+        assert_eq!(
+            raw.headers,
+            json!({ "Content-Type": "application/x-www-form-urlencoded" })
+        );
+        let request = Request {
+            headers: json!({ "Content-Type": "text/csv" }),
+            body_kind: BodyKind::Text,
+            body: Some(json!("a,b")),
+            ..request
+        };
+        let raw = prepare_request(&request, &HashMap::new()).unwrap();
+        assert_eq!(raw.headers, json!({ "Content-Type": "text/csv" }));
+    }
 }
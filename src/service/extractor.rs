@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use regex::Regex;
+use serde_json::Value;
+use url::Url;
+
+use crate::{
+    entity::{execution::RawHttpResponse, extractor::Extractor},
+    service::{
+        assertion::{body_text, header, text},
+        cookie,
+    },
+};
+
+// the values extracted from the response to the url by variable name, an
+// extractor which finds nothing or can not be evaluated is skipped.
+pub(crate) fn extract(
+    extractors: &[Extractor],
+    response: &RawHttpResponse,
+    url: &Url,
+) -> HashMap<String, String> {
+    extractors
+        .iter()
+        .filter_map(|extractor| match find(extractor, response, url) {
+            Ok(Some(value)) => Some((extractor.variable().to_string(), value)),
+            Ok(None) => {
+                tracing::warn!("extractor of {} found nothing", extractor.variable());
+                None
+            }
+            Err(e) => {
+                tracing::warn!("extractor of {} failed: {}", extractor.variable(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn find(extractor: &Extractor, response: &RawHttpResponse, url: &Url) -> Result<Option<String>> {
+    Ok(match extractor {
+        Extractor::JsonPath { path, .. } => jsonpath_lib::select(&response.body, path)?
+            .first()
+            .map(|value| text(value)),
+        Extractor::Header { name, .. } => header(response, name).map(|value| match value {
+            // the first of a repeated header
+            Value::Array(values) => values.first().map(text).unwrap_or_default(),
+            other => text(other),
+        }),
+        Extractor::Regex { pattern, group, .. } => {
+            let group = group.unwrap_or_default();
+            match Regex::new(pattern)?.captures(&body_text(response)) {
+                Some(captures) => Some(
+                    captures
+                        .get(group)
+                        .ok_or_else(|| anyhow!("no group {} in {}", group, pattern))?
+                        .as_str()
+                        .to_string(),
+                ),
+                None => None,
+            }
+        }
+        // the cookies are read the way the jar reads them
+        Extractor::Cookie { name, .. } => cookie::set_by(url, &response.headers, Local::now())
+            .into_iter()
+            .find(|cookie| cookie.name == *name)
+            .map(|cookie| cookie.value),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::entity::id::Id;
+
+    use super::*;
+
+    fn response(headers: Value, body: &[u8]) -> RawHttpResponse {
+        RawHttpResponse {
+            id: Id::new(),
+            version: "HTTP/1.1".to_string(),
+            status_code: 200,
+            status_message: "OK".to_string(),
+            headers,
+            size: body.len() as i64,
+            content_type: "application/json".to_string(),
+            body: serde_json::from_slice(body).unwrap_or(Value::Null),
+            raw_body: body.to_vec(),
+            truncated: false,
+        }
+    }
+
+    fn extract_from(response: &RawHttpResponse, extractors: Value) -> HashMap<String, String> {
+        let extractors = serde_json::from_value::<Vec<Extractor>>(extractors).unwrap();
+        let url = Url::parse("https://api.example.com/v1/login").unwrap();
+        extract(&extractors, response, &url)
+    }
+
+    #[test]
+    fn json_path_and_header() {
+        let response = response(
+            json!({"X-Request-Id": "abc", "link": ["<a>", "<b>"]}),
+            br#"{"token": "t0k", "user": {"id": 7}}"#,
+        );
+        let extracted = extract_from(
+            &response,
+            json!([
+                {"type": "json_path", "variable": "token", "path": "$.token"},
+                {"type": "json_path", "variable": "user", "path": "$.user.id"},
+                {"type": "json_path", "variable": "missing", "path": "$.missing"},
+                {"type": "header", "variable": "request", "name": "x-request-id"},
+                {"type": "header", "variable": "link", "name": "Link"},
+            ]),
+        );
+        assert_eq!(
+            extracted,
+            HashMap::from([
+                ("token".to_string(), "t0k".to_string()),
+                ("user".to_string(), "7".to_string()),
+                ("request".to_string(), "abc".to_string()),
+                ("link".to_string(), "<a>".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn regex_on_the_raw_body() {
+        // a body which is not JSON is null in `body`
+        let response = response(json!({}), b"<input name=\"csrf\" value=\"x9y8\">");
+        let extracted = extract_from(
+            &response,
+            json!([
+                {"type": "regex", "variable": "csrf", "pattern": "value=\"([^\"]+)\"", "group": 1},
+                {"type": "regex", "variable": "whole", "pattern": "name=\"[a-z]+\""},
+                {"type": "regex", "variable": "no_group", "pattern": "value", "group": 2},
+                {"type": "regex", "variable": "invalid", "pattern": "("},
+            ]),
+        );
+        assert_eq!(
+            extracted,
+            HashMap::from([
+                ("csrf".to_string(), "x9y8".to_string()),
+                ("whole".to_string(), "name=\"csrf\"".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn cookie_like_the_jar() {
+        let response = response(
+            json!({"set-cookie": [
+                "session=\"s1\"; Path=/; HttpOnly",
+                "theme=dark",
+                "other=x; Domain=elsewhere.com",
+            ]}),
+            b"",
+        );
+        let extracted = extract_from(
+            &response,
+            json!([
+                {"type": "cookie", "variable": "session", "name": "session"},
+                {"type": "cookie", "variable": "theme", "name": "theme"},
+                {"type": "cookie", "variable": "other", "name": "other"},
+            ]),
+        );
+        // the cookie of another domain is not set, the quotes are dropped
+        assert_eq!(
+            extracted,
+            HashMap::from([
+                ("session".to_string(), "s1".to_string()),
+                ("theme".to_string(), "dark".to_string()),
+            ])
+        );
+    }
+}
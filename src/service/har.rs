@@ -0,0 +1,660 @@
+use std::net::SocketAddr;
+
+use anyhow::Result;
+use axum::http::StatusCode;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::{
+    api::error::Error,
+    entity::{
+        body::{BodyKind, Part},
+        execution::{Execution, Hop, RawHttpRequest, RawHttpResponse, Timings as ExecutionTimings},
+        id::Id,
+        request::Request,
+    },
+    service::{assertion::text, body, execution, import},
+    storage::{store, Filter, Storage},
+};
+
+const VERSION: &str = "1.2";
+// the number of executions exported to an archive by default.
+pub(crate) const EXPORT_LIMIT: usize = 100;
+
+// an HTTP Archive, see http://www.softwareishard.com/blog/har-12-spec/
+//
+// only the fields flytrap reads or writes are modelled, the others are
+// ignored on import and left out of the export.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Har {
+    pub(crate) log: Log,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Log {
+    pub(crate) version: String,
+    pub(crate) creator: Creator,
+    pub(crate) entries: Vec<Entry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Creator {
+    pub(crate) name: String,
+    pub(crate) version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Entry {
+    pub(crate) started_date_time: String,
+    // the total time of the request in milliseconds
+    pub(crate) time: f64,
+    pub(crate) request: HarRequest,
+    pub(crate) response: HarResponse,
+    #[serde(default)]
+    pub(crate) cache: Value,
+    #[serde(default)]
+    pub(crate) timings: Timings,
+    #[serde(
+        default,
+        rename = "serverIPAddress",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) server_ip_address: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HarRequest {
+    pub(crate) method: String,
+    pub(crate) url: String,
+    pub(crate) http_version: String,
+    #[serde(default)]
+    pub(crate) cookies: Vec<Value>,
+    #[serde(default)]
+    pub(crate) headers: Vec<NameValue>,
+    #[serde(default)]
+    pub(crate) query_string: Vec<NameValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) post_data: Option<PostData>,
+    #[serde(default = "unknown_size")]
+    pub(crate) headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub(crate) body_size: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HarResponse {
+    pub(crate) status: i32,
+    pub(crate) status_text: String,
+    pub(crate) http_version: String,
+    #[serde(default)]
+    pub(crate) cookies: Vec<Value>,
+    #[serde(default)]
+    pub(crate) headers: Vec<NameValue>,
+    pub(crate) content: Content,
+    #[serde(default, rename = "redirectURL")]
+    pub(crate) redirect_url: String,
+    #[serde(default = "unknown_size")]
+    pub(crate) headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub(crate) body_size: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct NameValue {
+    pub(crate) name: String,
+    pub(crate) value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PostData {
+    #[serde(default)]
+    pub(crate) mime_type: String,
+    #[serde(default)]
+    pub(crate) text: String,
+    // the fields of a form
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) params: Vec<Param>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Param {
+    pub(crate) name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) file_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) content_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Content {
+    pub(crate) size: i64,
+    #[serde(default)]
+    pub(crate) mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) text: Option<String>,
+    // `base64` when the text is the base64 of a binary body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) encoding: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) comment: Option<String>,
+}
+
+// -1 for a phase which did not happen, the connection includes the handshake.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Timings {
+    #[serde(default = "unknown_time")]
+    pub(crate) dns: f64,
+    #[serde(default = "unknown_time")]
+    pub(crate) connect: f64,
+    #[serde(default = "unknown_time")]
+    pub(crate) ssl: f64,
+    pub(crate) send: f64,
+    pub(crate) wait: f64,
+    pub(crate) receive: f64,
+}
+
+impl Timings {
+    // the time of the entry, the handshake is counted with the connection.
+    fn total(&self) -> f64 {
+        [self.dns, self.connect, self.send, self.wait, self.receive]
+            .into_iter()
+            .filter(|time| *time > 0.0)
+            .sum()
+    }
+}
+
+fn unknown_time() -> f64 {
+    -1.0
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+fn name_values(headers: &Value) -> Vec<NameValue> {
+    let Some(headers) = headers.as_object() else {
+        return Vec::new();
+    };
+    headers
+        .iter()
+        .flat_map(|(name, value)| {
+            match value {
+                // a repeated header is one entry per value
+                Value::Array(values) => values.iter().map(text).collect(),
+                other => vec![text(other)],
+            }
+            .into_iter()
+            .map(move |value| NameValue {
+                name: name.clone(),
+                value,
+            })
+        })
+        .collect()
+}
+
+// the value of the header, empty when it is not set.
+fn header(headers: &Value, name: &str) -> String {
+    headers
+        .as_object()
+        .and_then(|headers| headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)))
+        .map(|(_, v)| text(v))
+        .unwrap_or_default()
+}
+
+// the latest executions of the workspace, or of one of its requests, oldest
+// first.
+pub(crate) async fn export_latest(
+    workspace_id: Id,
+    request_id: Option<Id>,
+    limit: usize,
+) -> Result<Har, Error> {
+    let mut filter = Filter::default();
+    filter.and_eq("workspace_id", workspace_id);
+    if let Some(request_id) = request_id {
+        filter.and_eq("request_id", request_id);
+    }
+    filter.order_desc("request_time").page(0, limit);
+    let mut executions = store().list::<Execution>(&filter).await?;
+    executions.reverse();
+    export(executions).await
+}
+
+// the archive of the executions, with the requests and responses they
+// recorded.
+pub(crate) async fn export(executions: Vec<Execution>) -> Result<Har, Error> {
+    let mut entries = Vec::with_capacity(executions.len());
+    for execution in executions {
+        let request = store()
+            .by_id::<RawHttpRequest>(execution.request)
+            .await?
+            .ok_or_else(|| Error::NotFound)?;
+        let response = store()
+            .by_id::<RawHttpResponse>(execution.response)
+            .await?
+            .ok_or_else(|| Error::NotFound)?;
+        entries.extend(execution_entries(&execution, request, response));
+    }
+    Ok(Har {
+        log: Log {
+            version: VERSION.to_string(),
+            creator: Creator {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            entries,
+        },
+    })
+}
+
+fn post_data(request: &RawHttpRequest, body: &Value) -> PostData {
+    let kind = request.body_kind;
+    let mime_type = match header(&request.headers, "content-type") {
+        mime_type if mime_type.is_empty() => kind.content_type().to_string(),
+        mime_type => mime_type,
+    };
+    let params = match kind {
+        BodyKind::Form => body::form_fields(body)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, value)| Param {
+                name,
+                value: Some(value),
+                file_name: None,
+                content_type: None,
+            })
+            .collect(),
+        BodyKind::Multipart => body::parts(body)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|part| Param {
+                name: part.name,
+                value: part.value,
+                file_name: part.filename,
+                content_type: part.content_type,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    PostData {
+        mime_type,
+        text: body::to_text(kind, body).unwrap_or_default(),
+        params,
+    }
+}
+
+// an entry per redirect followed by the execution and one for its response.
+// the requests of the redirects are the ones the client made of the request it
+// recorded.
+fn execution_entries(
+    execution: &Execution,
+    request: RawHttpRequest,
+    response: RawHttpResponse,
+) -> Vec<Entry> {
+    let hops = serde_json::from_value::<Vec<Hop>>(execution.redirects.clone()).unwrap_or_default();
+    let mut entries = Vec::with_capacity(hops.len() + 1);
+    let mut request = request;
+    let mut started = execution.request_time;
+    for hop in hops {
+        let next = StatusCode::from_u16(hop.status_code as u16)
+            .map_err(anyhow::Error::from)
+            .and_then(|status| execution::redirected(&request, status, &hop.location))
+            .unwrap_or_else(|_| RawHttpRequest {
+                method: hop.method.clone(),
+                url: hop.location.clone(),
+                ..request.clone()
+            });
+        let time = (hop.response_time - hop.request_time).num_milliseconds() as f64;
+        let response = HarResponse {
+            status: hop.status_code,
+            status_text: hop.status_message,
+            http_version: request.version.clone(),
+            cookies: Vec::new(),
+            content: Content {
+                size: 0,
+                mime_type: header(&hop.headers, "content-type"),
+                text: None,
+                encoding: None,
+                comment: None,
+            },
+            headers: name_values(&hop.headers),
+            redirect_url: hop.location,
+            headers_size: -1,
+            body_size: 0,
+        };
+        entries.push(entry(
+            hop.request_time,
+            request,
+            response,
+            timings(hop.timings, time),
+            None,
+        ));
+        request = next;
+        started = hop.response_time;
+    }
+    let time = (execution.response_time - started).num_milliseconds() as f64;
+    let timings = timings(
+        serde_json::from_value(execution.timings.clone()).unwrap_or_default(),
+        time,
+    );
+    let server_ip_address = execution
+        .remote_addr
+        .as_ref()
+        .and_then(|addr| addr.parse::<SocketAddr>().ok())
+        .map(|addr| addr.ip().to_string());
+    entries.push(entry(
+        started,
+        request,
+        har_response(response),
+        timings,
+        server_ip_address,
+    ));
+    entries
+}
+
+fn entry(
+    started: DateTime<Local>,
+    request: RawHttpRequest,
+    response: HarResponse,
+    timings: Timings,
+    server_ip_address: Option<String>,
+) -> Entry {
+    let query_string = Url::parse(&request.url)
+        .map(|url| {
+            url.query_pairs()
+                .map(|(name, value)| NameValue {
+                    name: name.into_owned(),
+                    value: value.into_owned(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let post_data = request.body.as_ref().map(|body| post_data(&request, body));
+    Entry {
+        started_date_time: started.to_rfc3339(),
+        time: timings.total(),
+        request: HarRequest {
+            body_size: post_data.as_ref().map_or(0, |data| data.text.len() as i64),
+            method: request.method,
+            url: request.url,
+            http_version: request.version,
+            cookies: Vec::new(),
+            headers: name_values(&request.headers),
+            query_string,
+            post_data,
+            headers_size: -1,
+        },
+        response,
+        cache: Value::Object(Map::new()),
+        timings,
+        server_ip_address,
+    }
+}
+
+fn har_response(response: RawHttpResponse) -> HarResponse {
+    // a body which is not UTF-8 is kept as base64
+    let comment = response
+        .truncated
+        .then(|| format!("the body is cut at {} bytes", response.size));
+    let (body, encoding) = match String::from_utf8(response.raw_body) {
+        Ok(body) => (body, None),
+        Err(e) => (STANDARD.encode(e.into_bytes()), Some("base64".to_string())),
+    };
+    HarResponse {
+        status: response.status_code,
+        status_text: response.status_message,
+        http_version: response.version,
+        cookies: Vec::new(),
+        content: Content {
+            size: response.size,
+            mime_type: response.content_type,
+            text: Some(body),
+            encoding,
+            comment,
+        },
+        redirect_url: header(&response.headers, "location"),
+        headers: name_values(&response.headers),
+        headers_size: -1,
+        body_size: response.size,
+    }
+}
+
+// the time until the first byte is all waiting but for the phases of the
+// connection, an exchange without timings was all waiting.
+fn timings(timings: ExecutionTimings, time: f64) -> Timings {
+    if timings.ttfb_ms == 0.0 && timings.download_ms == 0.0 {
+        return Timings {
+            dns: -1.0,
+            connect: -1.0,
+            ssl: -1.0,
+            send: 0.0,
+            wait: time,
+            receive: 0.0,
+        };
+    }
+    let dns = timings.dns_ms.unwrap_or(0.0);
+    let connect = timings.connect_ms.unwrap_or(0.0) + timings.tls_ms.unwrap_or(0.0);
+    Timings {
+        dns: timings.dns_ms.unwrap_or(-1.0),
+        connect: timings.connect_ms.map_or(-1.0, |_| connect),
+        ssl: timings.tls_ms.unwrap_or(-1.0),
+        send: 0.0,
+        wait: (timings.ttfb_ms - dns - connect).max(0.0),
+        receive: timings.download_ms,
+    }
+}
+
+// one request per entry of the archive.
+pub(crate) fn import(har: Har) -> Result<Vec<Request>> {
+    har.log
+        .entries
+        .into_iter()
+        .map(|entry| into_request(entry.request))
+        .collect()
+}
+
+fn into_request(request: HarRequest) -> Result<Request> {
+    let url = Url::parse(&request.url)?;
+    let (host, path, query) = import::split_url(&url);
+    let headers = request
+        .headers
+        .into_iter()
+        // http/2 pseudo headers and the length, which is computed when sent
+        .filter(|header| {
+            !header.name.starts_with(':') && !header.name.eq_ignore_ascii_case("content-length")
+        })
+        .map(|header| (header.name, Value::String(header.value)))
+        .collect::<Map<_, _>>();
+    let (body_kind, body) = match request.post_data {
+        Some(data) => into_body(data),
+        None => (BodyKind::Json, None),
+    };
+    Ok(Request {
+        id: Id::new(),
+        name: format!("{} {}", request.method, path),
+        method: request.method,
+        path,
+        query,
+        host,
+        headers: Value::Object(headers),
+        body_kind,
+        body,
+        assertions: Value::Array(Vec::new()),
+        extractors: Value::Array(Vec::new()),
+        ..Default::default()
+    })
+}
+
+// the kind of the body by its mime type, a JSON body which does not parse is
+// kept as text.
+fn into_body(data: PostData) -> (BodyKind, Option<Value>) {
+    let mime_type = data.mime_type.to_lowercase();
+    if mime_type.starts_with(BodyKind::Multipart.content_type()) {
+        let parts = data
+            .params
+            .into_iter()
+            .map(|param| match param.file_name {
+                Some(filename) => Part {
+                    name: param.name,
+                    value: None,
+                    filename: Some(filename),
+                    content_type: param.content_type,
+                    data: Some(STANDARD.encode(param.value.unwrap_or_default())),
+                },
+                None => Part {
+                    name: param.name,
+                    value: param.value,
+                    filename: None,
+                    content_type: param.content_type,
+                    data: None,
+                },
+            })
+            .collect::<Vec<_>>();
+        return (BodyKind::Multipart, Some(serde_json::json!(parts)));
+    }
+    if mime_type.starts_with(BodyKind::Form.content_type()) {
+        let form = if data.text.is_empty() {
+            let fields = data
+                .params
+                .into_iter()
+                .map(|param| (param.name, Value::String(param.value.unwrap_or_default())))
+                .collect::<Map<_, _>>();
+            Value::Object(fields)
+        } else {
+            Value::String(data.text)
+        };
+        return (BodyKind::Form, Some(form));
+    }
+    if mime_type.contains("json") {
+        if let Ok(json) = serde_json::from_str(&data.text) {
+            return (BodyKind::Json, Some(json));
+        }
+    }
+    (BodyKind::Text, Some(Value::String(data.text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use serde_json::json;
+
+    use super::*;
+
+    fn execution() -> (Execution, RawHttpRequest, RawHttpResponse) {
+        let start = Local::now();
+        let hop = Hop {
+            method: "POST".to_string(),
+            url: "https://example.com/login?next=a&next=b".to_string(),
+            status_code: 302,
+            status_message: "Found".to_string(),
+            location: "https://example.com/home".to_string(),
+            headers: json!({ "location": "/home" }),
+            request_time: start,
+            response_time: start + Duration::milliseconds(40),
+            timings: ExecutionTimings {
+                dns_ms: Some(5.0),
+                connect_ms: Some(10.0),
+                tls_ms: Some(15.0),
+                ttfb_ms: 40.0,
+                download_ms: 0.0,
+            },
+        };
+        let request = RawHttpRequest {
+            id: Id::new(),
+            method: "POST".to_string(),
+            url: hop.url.clone(),
+            version: "HTTP/1.1".to_string(),
+            headers: json!({ "Content-Type": "application/json", "X-Id": "7" }),
+            body_kind: BodyKind::Json,
+            body: Some(json!({ "user": "x" })),
+        };
+        let response = RawHttpResponse {
+            id: Id::new(),
+            version: "HTTP/1.1".to_string(),
+            status_code: 200,
+            status_message: "OK".to_string(),
+            headers: json!({ "content-type": "text/plain" }),
+            size: 5,
+            content_type: "text/plain".to_string(),
+            body: json!("hello"),
+            raw_body: b"hello".to_vec(),
+            truncated: false,
+        };
+        let execution = Execution {
+            request_time: start,
+            response_time: start + Duration::milliseconds(60),
+            redirects: json!([hop]),
+            timings: json!({ "ttfb_ms": 12.0, "download_ms": 3.0 }),
+            remote_addr: Some("10.0.0.1:443".to_string()),
+            ..Default::default()
+        };
+        (execution, request, response)
+    }
+
+    #[test]
+    fn an_entry_per_redirect() {
+        let (execution, request, response) = execution();
+        let entries = execution_entries(&execution, request, response);
+        assert_eq!(entries.len(), 2);
+
+        let hop = &entries[0];
+        assert_eq!(hop.request.method, "POST");
+        assert_eq!(hop.request.query_string.len(), 2);
+        assert_eq!(hop.response.status, 302);
+        assert_eq!(hop.response.redirect_url, "https://example.com/home");
+        assert_eq!(hop.timings.connect, 25.0);
+        assert_eq!(hop.timings.wait, 10.0);
+        assert_eq!(hop.time, 40.0);
+        assert_eq!(hop.server_ip_address, None);
+
+        // the redirect is followed with a GET without the body
+        let last = &entries[1];
+        assert_eq!(last.request.method, "GET");
+        assert_eq!(last.request.url, "https://example.com/home");
+        assert!(last.request.post_data.is_none());
+        assert_eq!(last.response.content.text.as_deref(), Some("hello"));
+        assert_eq!(last.time, 15.0);
+        assert_eq!(last.server_ip_address.as_deref(), Some("10.0.0.1"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let (execution, request, response) = execution();
+        let har = Har {
+            log: Log {
+                version: VERSION.to_string(),
+                creator: Creator {
+                    name: "flytrap".to_string(),
+                    version: "0".to_string(),
+                },
+                entries: execution_entries(&execution, request, response),
+            },
+        };
+        let har = serde_json::from_value::<Har>(serde_json::to_value(har).unwrap()).unwrap();
+        let requests = import(har).unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].host, "https://example.com");
+        assert_eq!(requests[0].path, "/login");
+        assert_eq!(requests[0].query, json!({ "next": ["a", "b"] }));
+        assert_eq!(
+            requests[0].headers,
+            json!({ "Content-Type": "application/json", "X-Id": "7" })
+        );
+        assert_eq!(requests[0].body_kind, BodyKind::Json);
+        assert_eq!(requests[0].body, Some(json!({ "user": "x" })));
+        assert_eq!(requests[1].method, "GET");
+        assert_eq!(requests[1].path, "/home");
+        assert_eq!(requests[1].body, None);
+    }
+}
@@ -0,0 +1,167 @@
+use anyhow::Result;
+use axum::response::IntoResponse;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx_crud::Schema;
+use url::Url;
+
+use crate::{
+    api::{
+        error::Error,
+        resp::{ExpectRowsAffected, FetchOne},
+    },
+    entity::{environment::Environment, id::Id, request::Request},
+    service::event::{Action, Event},
+    storage::{store, Storage},
+};
+
+// save the requests parsed by an importer in the workspace, as the API creates
+// them one by one.
+pub(crate) async fn save_requests(
+    workspace_id: Id,
+    requests: Vec<Request>,
+) -> Result<Vec<Request>> {
+    let mut saved = Vec::with_capacity(requests.len());
+    for request in requests {
+        let request = Request {
+            workspace_id,
+            ..request
+        };
+        let id = request.id;
+        store()
+            .create(request)
+            .await
+            .map_err(|e| Error::CreateFailed(e.to_string()))?
+            .expect(1)?;
+        let request = store()
+            .by_id::<Request>(id)
+            .await?
+            .ok_or_else(|| Error::NotFound)?;
+        Event::new(
+            Request::table_name(),
+            Action::Created,
+            request.id.to_string(),
+            &request,
+        )
+        .in_workspace(workspace_id)
+        .publish();
+        saved.push(request);
+    }
+    Ok(saved)
+}
+
+// what an importer made of a document, the parts of the document it does not
+// support are reported rather than failing the import.
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct Imported {
+    pub(crate) requests: Vec<Request>,
+    // the variables defined by the document, if any
+    pub(crate) environment: Option<Environment>,
+    pub(crate) unsupported: Vec<String>,
+}
+
+impl IntoResponse for Imported {
+    fn into_response(self) -> axum::response::Response {
+        FetchOne::new(self).into_response()
+    }
+}
+
+// save the requests and the environment of the import in the workspace.
+pub(crate) async fn save(workspace_id: Id, imported: Imported) -> Result<Imported> {
+    let requests = save_requests(workspace_id, imported.requests).await?;
+    let environment = match imported.environment {
+        Some(environment) => {
+            let environment = Environment {
+                workspace_id,
+                ..environment
+            };
+            let id = environment.id;
+            store()
+                .create(environment)
+                .await
+                .map_err(|e| Error::CreateFailed(e.to_string()))?
+                .expect(1)?;
+            let environment = store()
+                .by_id::<Environment>(id)
+                .await?
+                .ok_or_else(|| Error::NotFound)?;
+            Event::new(
+                Environment::table_name(),
+                Action::Created,
+                environment.id.to_string(),
+                &environment,
+            )
+            .in_workspace(workspace_id)
+            .publish();
+            Some(environment)
+        }
+        None => None,
+    };
+    Ok(Imported {
+        requests,
+        environment,
+        unsupported: imported.unsupported,
+    })
+}
+
+// the host, path and query of the url the way a request keeps them.
+pub(crate) fn split_url(url: &Url) -> (String, String, Value) {
+    let host = match url.port() {
+        Some(port) => format!(
+            "{}://{}:{}",
+            url.scheme(),
+            url.host_str().unwrap_or_default(),
+            port
+        ),
+        None => format!("{}://{}", url.scheme(), url.host_str().unwrap_or_default()),
+    };
+    let query = query(
+        url.query_pairs()
+            .map(|(k, v)| (k.into_owned(), v.into_owned())),
+    );
+    (host, url.path().to_string(), query)
+}
+
+// the query of a request, a key given more than once keeps all its values in
+// an array.
+pub(crate) fn query(pairs: impl IntoIterator<Item = (String, String)>) -> Value {
+    let mut query = Map::new();
+    for (k, v) in pairs {
+        match query.get_mut(&k) {
+            Some(Value::Array(values)) => values.push(Value::String(v)),
+            Some(value) => *value = Value::Array(vec![value.take(), Value::String(v)]),
+            None => {
+                query.insert(k, Value::String(v));
+            }
+        }
+    }
+    Value::Object(query)
+}
+
+// split an url which may not parse, e.g. `{{baseUrl}}/users?page={{page}}`.
+pub(crate) fn split_raw(raw: &str) -> (String, String, Vec<(String, String)>) {
+    let (base, query) = raw.split_once('?').unwrap_or((raw, ""));
+    let host_start = base.find("://").map_or(0, |i| i + 3);
+    let (host, path) = match base[host_start..].find('/') {
+        Some(i) => base.split_at(host_start + i),
+        None => (base, "/"),
+    };
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((k, v)) => (k.to_string(), v.to_string()),
+            None => (pair.to_string(), String::new()),
+        })
+        .collect();
+    (with_scheme(host.to_string()), path.to_string(), query)
+}
+
+// http is the default scheme, a variable may hold the scheme.
+pub(crate) fn with_scheme(host: String) -> String {
+    if host.contains("://") || host.starts_with("{{") {
+        host
+    } else {
+        format!("http://{}", host)
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use axum::http::HeaderMap;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+use crate::{
+    api::{error::Error, resp::ExpectRowsAffected},
+    entity::{id::Id, mock::MockRoute},
+    storage::{store, Filter, Storage},
+};
+
+const ANY_METHOD: &str = "*";
+
+// held shared while a hit is counted and exclusively while a route is read
+// and written back, so that writing a route back never overwrites the count
+// with a stale one.
+static HITS: Lazy<RwLock<()>> = Lazy::new(Default::default);
+
+// the request as seen by the mock server.
+pub(crate) struct MockRequest<'a> {
+    pub(crate) method: &'a str,
+    pub(crate) path: &'a str,
+    pub(crate) query: &'a HashMap<String, String>,
+    pub(crate) headers: &'a HeaderMap,
+    pub(crate) body: &'a [u8],
+}
+
+// find the route answering the request and count the hit.
+pub(crate) async fn find_route(request: &MockRequest<'_>) -> Result<Option<MockRoute>> {
+    let routes = store()
+        .list::<MockRoute>(
+            Filter::default()
+                .and_in("method", &[request.method, ANY_METHOD])
+                .order_desc("priority")
+                .order_asc("created_at"),
+        )
+        .await?;
+    let body = serde_json::from_slice::<Value>(request.body).ok();
+    let route = routes.into_iter().find(|route| {
+        match_path(&route.path, request.path)
+            && match_query(&route.query, request.query)
+            && match_headers(&route.headers, request.headers)
+            && match &route.body {
+                Some(expected) => body.as_ref().is_some_and(|b| contains(b, expected)),
+                None => true,
+            }
+    });
+    if let Some(ref route) = route {
+        let _counting = HITS.read().await;
+        store()
+            .increment::<MockRoute>(route.id, "hits", |route| route.hits += 1)
+            .await?;
+    }
+    Ok(route)
+}
+
+// change the route with `update`, which must leave the hit counter alone,
+// returns the route written back.
+pub(crate) async fn update_route(
+    id: Id,
+    update: impl FnOnce(MockRoute) -> MockRoute,
+) -> Result<MockRoute, Error> {
+    let _writing = HITS.write().await;
+    let route = store()
+        .by_id::<MockRoute>(id)
+        .await?
+        .ok_or_else(|| Error::NotFound)?;
+    let route = update(route);
+    store().update(route.clone()).await?.expect(1)?;
+    Ok(route)
+}
+
+// count the hits of the route from zero again.
+pub(crate) async fn reset_hits(id: Id) -> Result<MockRoute, Error> {
+    update_route(id, |route| MockRoute { hits: 0, ..route }).await
+}
+
+fn match_path(pattern: &str, path: &str) -> bool {
+    let mut pattern = pattern.trim_matches('/').split('/');
+    let mut path = path.trim_matches('/').split('/');
+    loop {
+        match (pattern.next(), path.next()) {
+            (Some(p), _) if p.starts_with('*') => return true,
+            (Some(p), Some(s)) if p.starts_with(':') || p == s => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+fn as_str(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn match_query(expected: &Value, query: &HashMap<String, String>) -> bool {
+    let Some(expected) = expected.as_object() else {
+        return true;
+    };
+    expected
+        .iter()
+        .all(|(k, v)| query.get(k).is_some_and(|actual| *actual == as_str(v)))
+}
+
+fn match_headers(expected: &Value, headers: &HeaderMap) -> bool {
+    let Some(expected) = expected.as_object() else {
+        return true;
+    };
+    expected.iter().all(|(k, v)| {
+        headers
+            .get_all(k.as_str())
+            .iter()
+            .any(|actual| actual.to_str().is_ok_and(|actual| actual == as_str(v)))
+    })
+}
+
+// whether `expected` is a part of `actual`, objects may have more keys than
+// expected, everything else has to be equal.
+pub(crate) fn contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected
+            .iter()
+            .all(|(k, v)| actual.get(k).is_some_and(|a| contains(a, v))),
+        _ => actual == expected,
+    }
+}
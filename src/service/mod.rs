@@ -1 +1,20 @@
+pub(crate) mod assertion;
+pub(crate) mod auth;
+pub(crate) mod body;
+pub(crate) mod client;
+pub(crate) mod collection;
+pub(crate) mod connector;
+pub(crate) mod cookie;
+pub(crate) mod curl;
+pub(crate) mod event;
 pub(crate) mod execution;
+pub(crate) mod extractor;
+pub(crate) mod har;
+pub(crate) mod import;
+pub(crate) mod mock;
+pub(crate) mod openapi;
+pub(crate) mod postman;
+pub(crate) mod template;
+pub(crate) mod timing;
+pub(crate) mod variable;
+pub(crate) mod workspace;
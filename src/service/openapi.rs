@@ -0,0 +1,580 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::{Map, Value};
+
+use crate::{
+    entity::{
+        body::{BodyKind, Part},
+        environment::Environment,
+        id::Id,
+        request::Request,
+    },
+    service::{
+        assertion::text,
+        import::{self, Imported},
+    },
+};
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+// how deep references and schemas are followed, recursive schemas stop there
+const MAX_DEPTH: usize = 8;
+// the host of the requests when the servers are relative to the document
+const BASE_URL: &str = "baseUrl";
+
+// import an OpenAPI 3.0 or 3.1 document in JSON or YAML, one request per
+// operation.
+//
+// the host and path prefix come from the first server, whose variables become
+// an environment. the path parameters are variables unless they have an
+// example, those without one are added to the environment empty. the query
+// parameters, headers and body are examples taken from the document or made up
+// from their schemas.
+pub(crate) fn import(document: &str) -> Result<Imported> {
+    // YAML is a superset of JSON
+    let document = serde_yaml::from_str::<Value>(document)?;
+    let version = document
+        .get("openapi")
+        .map(text)
+        .ok_or_else(|| anyhow!("not an OpenAPI document, swagger 2 is not supported"))?;
+    if !version.starts_with("3.") {
+        bail!("unsupported OpenAPI version: {}", version);
+    }
+    let mut imported = Imported::default();
+    let mut variables = HashMap::new();
+    let server = document.pointer("/servers/0");
+    let url = server
+        .and_then(|server| server.get("url"))
+        .and_then(Value::as_str)
+        .unwrap_or("/");
+    if let Some(server_variables) = server
+        .and_then(|server| server.get("variables"))
+        .and_then(Value::as_object)
+    {
+        for (name, variable) in server_variables {
+            let default = variable.get("default").map(text).unwrap_or_default();
+            variables.insert(name.clone(), default);
+        }
+    }
+    let url = to_variables(url);
+    let (host, prefix) = if url.starts_with('/') {
+        variables.insert(BASE_URL.to_string(), "http://localhost".to_string());
+        (format!("{{{{{}}}}}", BASE_URL), url)
+    } else {
+        let (host, path, _) = import::split_raw(&url);
+        (host, path)
+    };
+    let prefix = prefix.trim_end_matches('/');
+    let spec = Spec {
+        root: &document,
+        host,
+    };
+    if document
+        .get("security")
+        .and_then(Value::as_array)
+        .is_some_and(|security| !security.is_empty())
+    {
+        imported
+            .unsupported
+            .push("security schemes are not supported".to_string());
+    }
+    let paths = document
+        .get("paths")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    for (path, item) in paths.iter() {
+        let item = spec.resolve(item);
+        let shared = item.get("parameters");
+        for method in METHODS {
+            let Some(operation) = item.get(*method) else {
+                continue;
+            };
+            let name = operation
+                .get("operationId")
+                .or_else(|| operation.get("summary"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{} {}", method.to_uppercase(), path));
+            let (request, path_variables) = spec.operation(
+                &name,
+                method,
+                &format!("{}{}", prefix, path),
+                shared,
+                operation,
+                &mut imported.unsupported,
+            );
+            for name in path_variables {
+                variables.entry(name).or_default();
+            }
+            imported.requests.push(request);
+        }
+    }
+    if !variables.is_empty() {
+        let name = document
+            .pointer("/info/title")
+            .and_then(Value::as_str)
+            .unwrap_or("openapi");
+        imported.environment = Some(Environment {
+            id: Id::new(),
+            name: name.to_string(),
+            variables: serde_json::json!(variables),
+            ..Default::default()
+        });
+    }
+    Ok(imported)
+}
+
+// `{name}` templates of OpenAPI as `{{name}}` variables.
+fn to_variables(template: &str) -> String {
+    template.replace('{', "{{").replace('}', "}}")
+}
+
+struct Spec<'a> {
+    root: &'a Value,
+    // the host of the first server
+    host: String,
+}
+
+impl<'a> Spec<'a> {
+    // the value a local `$ref` points to, other references are left as they
+    // are.
+    fn resolve(&self, mut value: &'a Value) -> &'a Value {
+        for _ in 0..MAX_DEPTH {
+            let Some(reference) = value.get("$ref").and_then(Value::as_str) else {
+                break;
+            };
+            match reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+            {
+                Some(target) => value = target,
+                None => break,
+            }
+        }
+        value
+    }
+
+    // the request of the operation and the path parameters it has no example
+    // of, which are left as variables.
+    fn operation(
+        &self,
+        name: &str,
+        method: &str,
+        path: &str,
+        shared: Option<&'a Value>,
+        operation: &'a Value,
+        unsupported: &mut Vec<String>,
+    ) -> (Request, Vec<String>) {
+        // the parameters of the operation override those of the path
+        let mut parameters: Vec<&Value> = Vec::new();
+        for parameter in [shared, operation.get("parameters")]
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_array)
+            .flatten()
+        {
+            let parameter = self.resolve(parameter);
+            parameters.retain(|p| {
+                p.get("name") != parameter.get("name") || p.get("in") != parameter.get("in")
+            });
+            parameters.push(parameter);
+        }
+        let mut path = to_variables(path);
+        let mut path_variables = Vec::new();
+        let mut query = Map::new();
+        let mut headers = Map::new();
+        let mut cookies = Vec::new();
+        for parameter in parameters {
+            let Some(parameter_name) = parameter.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            match parameter.get("in").and_then(Value::as_str) {
+                Some("path") => match self.parameter_example(parameter) {
+                    Some(example) => {
+                        path =
+                            path.replace(&format!("{{{{{}}}}}", parameter_name), &text(&example));
+                    }
+                    None => path_variables.push(parameter_name.to_string()),
+                },
+                Some("query") => {
+                    let value = self.parameter_value(parameter);
+                    query.insert(parameter_name.to_string(), Value::String(value));
+                }
+                Some("header") => {
+                    let value = self.parameter_value(parameter);
+                    headers.insert(parameter_name.to_string(), Value::String(value));
+                }
+                Some("cookie") => {
+                    let value = self.parameter_value(parameter);
+                    cookies.push(format!("{}={}", parameter_name, value));
+                }
+                _ => {}
+            }
+        }
+        if !cookies.is_empty() {
+            headers.insert("Cookie".to_string(), Value::String(cookies.join("; ")));
+        }
+        let (body_kind, body) = operation
+            .get("requestBody")
+            .map(|body| self.resolve(body))
+            .and_then(|body| self.body(name, body, &mut headers, unsupported))
+            .unzip();
+        let request = Request {
+            id: Id::new(),
+            name: name.to_string(),
+            method: method.to_uppercase(),
+            path,
+            query: Value::Object(query),
+            host: self.host.clone(),
+            headers: Value::Object(headers),
+            body_kind: body_kind.unwrap_or_default(),
+            body,
+            assertions: Value::Array(Vec::new()),
+            extractors: Value::Array(Vec::new()),
+            ..Default::default()
+        };
+        (request, path_variables)
+    }
+
+    // the example the document gives for the parameter.
+    fn parameter_example(&self, parameter: &Value) -> Option<Value> {
+        parameter
+            .get("example")
+            .cloned()
+            .or_else(|| self.first_example(parameter))
+            .or_else(|| {
+                let schema = self.resolve(parameter.get("schema")?);
+                schema.get("example").cloned()
+            })
+    }
+
+    // the example of the parameter, or one made up from its schema.
+    fn parameter_value(&self, parameter: &Value) -> String {
+        self.parameter_example(parameter)
+            .or_else(|| {
+                parameter
+                    .get("schema")
+                    .map(|schema| self.synthesize(schema, 0))
+            })
+            .filter(|example| !example.is_null())
+            .map(|example| text(&example))
+            .unwrap_or_default()
+    }
+
+    // the value of the first of the named `examples`.
+    fn first_example(&self, value: &Value) -> Option<Value> {
+        let examples = value.get("examples")?.as_object()?;
+        let (_, example) = examples.iter().next()?;
+        self.resolve(example).get("value").cloned()
+    }
+
+    // the example body of the first media type, JSON is preferred.
+    fn body(
+        &self,
+        name: &str,
+        body: &Value,
+        headers: &mut Map<String, Value>,
+        unsupported: &mut Vec<String>,
+    ) -> Option<(BodyKind, Value)> {
+        let content = body.get("content")?.as_object()?;
+        let (media_type, media) = content
+            .iter()
+            .find(|(media_type, _)| is_json(media_type))
+            .or_else(|| content.iter().next())?;
+        let example = media
+            .get("example")
+            .cloned()
+            .or_else(|| self.first_example(media))
+            .or_else(|| media.get("schema").map(|schema| self.synthesize(schema, 0)))
+            .unwrap_or_default();
+        let fields = || {
+            example
+                .as_object()
+                .map(|fields| {
+                    fields
+                        .iter()
+                        .map(|(k, v)| (k.clone(), text(v)))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        };
+        let essence = essence(media_type);
+        let body = if is_json(media_type) {
+            (BodyKind::Json, example.clone())
+        } else if essence == "application/x-www-form-urlencoded" {
+            let form = fields()
+                .into_iter()
+                .map(|(k, v)| (k, Value::String(v)))
+                .collect::<Map<_, _>>();
+            (BodyKind::Form, Value::Object(form))
+        } else if essence == "multipart/form-data" {
+            // the boundary is set when the request is sent
+            let parts = fields()
+                .into_iter()
+                .map(|(name, value)| Part {
+                    name,
+                    value: Some(value),
+                    filename: None,
+                    content_type: None,
+                    data: None,
+                })
+                .collect::<Vec<_>>();
+            return Some((BodyKind::Multipart, serde_json::json!(parts)));
+        } else if essence.starts_with("text/") || essence.ends_with("xml") {
+            (BodyKind::Text, Value::String(text(&example)))
+        } else {
+            unsupported.push(format!("{}: {} bodies are not supported", name, media_type));
+            return None;
+        };
+        headers.insert(
+            "Content-Type".to_string(),
+            Value::String(media_type.to_string()),
+        );
+        Some(body)
+    }
+
+    // an example value of the schema.
+    fn synthesize(&self, schema: &Value, depth: usize) -> Value {
+        let schema = self.resolve(schema);
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        for key in ["example", "default", "const"] {
+            if let Some(value) = schema.get(key) {
+                return value.clone();
+            }
+        }
+        for key in ["examples", "enum"] {
+            if let Some(value) = schema.get(key).and_then(|v| v.get(0)) {
+                return value.clone();
+            }
+        }
+        if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+            let mut merged = Map::new();
+            for schema in all_of {
+                if let Value::Object(fields) = self.synthesize(schema, depth + 1) {
+                    merged.extend(fields);
+                }
+            }
+            return Value::Object(merged);
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(schema) = schema.get(key).and_then(|v| v.get(0)) {
+                return self.synthesize(schema, depth + 1);
+            }
+        }
+        // 3.1 allows a list of types such as `["string", "null"]`
+        let kind = match schema.get("type") {
+            Some(Value::Array(kinds)) => kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|kind| *kind != "null"),
+            Some(kind) => kind.as_str(),
+            None if schema.get("properties").is_some() => Some("object"),
+            None => None,
+        };
+        match kind {
+            Some("object") => Value::Object(
+                schema
+                    .get("properties")
+                    .and_then(Value::as_object)
+                    .map(|properties| {
+                        properties
+                            .iter()
+                            .map(|(name, schema)| {
+                                (name.clone(), self.synthesize(schema, depth + 1))
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+            Some("array") => Value::Array(
+                schema
+                    .get("items")
+                    .map(|items| vec![self.synthesize(items, depth + 1)])
+                    .unwrap_or_default(),
+            ),
+            Some("string") => Value::String(
+                match schema.get("format").and_then(Value::as_str) {
+                    Some("date-time") => "2023-01-01T00:00:00Z",
+                    Some("date") => "2023-01-01",
+                    Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                    Some("email") => "user@example.com",
+                    Some("uri") | Some("url") => "https://example.com",
+                    _ => "string",
+                }
+                .to_string(),
+            ),
+            Some("integer") | Some("number") => Value::from(0),
+            Some("boolean") => Value::Bool(false),
+            _ => Value::Null,
+        }
+    }
+}
+
+// the type and subtype of the media type, lowercase and without parameters.
+fn essence(media_type: &str) -> String {
+    let essence = media_type.split(';').next().unwrap_or_default();
+    essence.trim().to_lowercase()
+}
+
+// `application/json` or `application/*+json`, with any parameters.
+fn is_json(media_type: &str) -> bool {
+    let essence = essence(media_type);
+    match essence.split_once('/') {
+        Some((kind, subtype)) => {
+            kind == "application" && (subtype == "json" || subtype.ends_with("+json"))
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const DOCUMENT: &str = r#"
+openapi: 3.0.3
+info:
+  title: shop
+servers:
+  - url: https://{region}.shop.test/v1/
+    variables:
+      region:
+        default: eu
+paths:
+  /users/{id}/orders/{orderId}:
+    parameters:
+      - name: id
+        in: path
+        required: true
+        schema: { type: integer }
+    get:
+      operationId: listOrders
+      parameters:
+        - name: orderId
+          in: path
+          example: 42
+        - name: limit
+          in: query
+          schema: { type: integer, default: 10 }
+        - name: X-Trace
+          in: header
+          schema: { type: string, format: uuid }
+        - name: session
+          in: cookie
+          example: abc
+  /users:
+    post:
+      requestBody:
+        content:
+          text/plain: {}
+          application/vnd.shop+json; charset=utf-8:
+            schema: { $ref: '#/components/schemas/User' }
+    put:
+      requestBody:
+        content:
+          application/x-www-form-urlencoded; charset=utf-8:
+            example: { name: x }
+    patch:
+      requestBody:
+        content:
+          application/octet-stream: {}
+components:
+  schemas:
+    User:
+      type: object
+      properties:
+        name: { type: string }
+        email: { type: string, format: email }
+        tags: { type: array, items: { type: string } }
+        admin: { type: [boolean, "null"] }
+"#;
+
+    #[test]
+    fn map_operations() {
+        let imported = import(DOCUMENT).unwrap();
+        assert_eq!(imported.requests.len(), 4);
+        let request = |name: &str| {
+            imported
+                .requests
+                .iter()
+                .find(|request| request.name == name)
+                .unwrap()
+        };
+
+        let list = request("listOrders");
+        assert_eq!(list.method, "GET");
+        assert_eq!(list.host, "https://{{region}}.shop.test");
+        assert_eq!(list.path, "/v1/users/{{id}}/orders/42");
+        assert_eq!(list.query, json!({ "limit": "10" }));
+        assert_eq!(
+            list.headers,
+            json!({
+                "X-Trace": "00000000-0000-0000-0000-000000000000",
+                "Cookie": "session=abc"
+            })
+        );
+
+        let create = request("POST /users");
+        assert_eq!(create.body_kind, BodyKind::Json);
+        assert_eq!(
+            create.body,
+            Some(json!({
+                "name": "string",
+                "email": "user@example.com",
+                "tags": ["string"],
+                "admin": false
+            }))
+        );
+        assert_eq!(
+            create.headers,
+            json!({ "Content-Type": "application/vnd.shop+json; charset=utf-8" })
+        );
+
+        let update = request("PUT /users");
+        assert_eq!(update.body_kind, BodyKind::Form);
+        assert_eq!(update.body, Some(json!({ "name": "x" })));
+
+        assert_eq!(
+            imported.unsupported,
+            ["PATCH /users: application/octet-stream bodies are not supported"]
+        );
+    }
+
+    #[test]
+    fn path_parameters_without_example_are_variables() {
+        let imported = import(DOCUMENT).unwrap();
+        let environment = imported.environment.unwrap();
+        assert_eq!(environment.name, "shop");
+        assert_eq!(environment.variables, json!({ "region": "eu", "id": "" }));
+    }
+
+    #[test]
+    fn relative_servers_and_versions() {
+        let imported = import("openapi: 3.1.0\npaths:\n  /ping:\n    get: {}\n").unwrap();
+        assert_eq!(imported.requests[0].host, "{{baseUrl}}");
+        assert_eq!(imported.requests[0].path, "/ping");
+        assert_eq!(
+            imported.environment.unwrap().variables,
+            json!({ "baseUrl": "http://localhost" })
+        );
+        assert!(import("swagger: '2.0'").is_err());
+        assert!(import("openapi: 4.0.0").is_err());
+    }
+
+    #[test]
+    fn json_media_types() {
+        assert!(is_json("application/json"));
+        assert!(is_json("Application/JSON; charset=utf-8"));
+        assert!(is_json("application/problem+json"));
+        assert!(!is_json("text/json+plain"));
+        assert!(!is_json("application/jsonl"));
+        assert!(!is_json("json"));
+    }
+}
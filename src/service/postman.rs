@@ -0,0 +1,594 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::{Map, Value};
+
+use crate::{
+    entity::{
+        auth::{ApiKeyLocation, Auth},
+        body::{BodyKind, Part},
+        environment::Environment,
+        id::Id,
+        request::Request,
+    },
+    service::{
+        assertion::text,
+        import::{self, Imported},
+    },
+};
+
+// import a Postman v2.1 collection, see
+// https://schema.postman.com/collection/json/v2.1.0/draft-07/docs/index.html
+//
+// every request of the collection and its folders becomes a request named
+// after its folders, the variables of the collection become an environment.
+// Postman uses the same `{{name}}` syntax for variables so they are kept as
+// they are. the auth of a folder or of the collection is inherited by its
+// requests. scripts, file bodies, the files of form-data bodies and OAuth2
+// grants other than client credentials are reported as unsupported.
+pub(crate) fn import(collection: &Value) -> Result<Imported> {
+    let info = collection
+        .get("info")
+        .ok_or_else(|| anyhow!("no info in the collection"))?;
+    let schema = info
+        .get("schema")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if !schema.contains("/v2.1.") {
+        bail!("not a Postman v2.1 collection: {}", schema);
+    }
+    let name = info
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("postman");
+    let mut imported = Imported::default();
+    check_unsupported(name, collection, &mut imported.unsupported);
+    walk(
+        items(collection),
+        &[],
+        collection.get("auth"),
+        &mut imported,
+    );
+    let variables = collection
+        .get("variable")
+        .and_then(Value::as_array)
+        .map(|variables| {
+            variables
+                .iter()
+                .filter(|variable| !disabled(variable))
+                .filter_map(|variable| {
+                    let key = variable.get("key")?.as_str()?;
+                    Some((key.to_string(), value_of(variable)))
+                })
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+    if !variables.is_empty() {
+        imported.environment = Some(Environment {
+            id: Id::new(),
+            name: name.to_string(),
+            variables: serde_json::json!(variables),
+            ..Default::default()
+        });
+    }
+    Ok(imported)
+}
+
+fn items(value: &Value) -> &[Value] {
+    value
+        .get("item")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn walk(items: &[Value], folders: &[&str], auth: Option<&Value>, imported: &mut Imported) {
+    for item in items {
+        let name = item
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("unnamed");
+        let mut path = folders.to_vec();
+        path.push(name);
+        let full_name = path.join(" / ");
+        check_unsupported(&full_name, item, &mut imported.unsupported);
+        // a folder
+        if item.get("item").is_some() {
+            let auth = item.get("auth").filter(|auth| !auth.is_null()).or(auth);
+            walk(self::items(item), &path, auth, imported);
+            continue;
+        }
+        let Some(request) = item.get("request") else {
+            imported
+                .unsupported
+                .push(format!("{}: no request", full_name));
+            continue;
+        };
+        match into_request(&full_name, request, auth, &mut imported.unsupported) {
+            Ok(request) => imported.requests.push(request),
+            Err(e) => imported.unsupported.push(format!("{}: {}", full_name, e)),
+        }
+    }
+}
+
+// the scripts of a collection, folder or request, its auth is mapped by
+// `into_auth` which reports the kinds it does not support.
+fn check_unsupported(name: &str, item: &Value, unsupported: &mut Vec<String>) {
+    if item
+        .get("event")
+        .and_then(Value::as_array)
+        .is_some_and(|events| !events.is_empty())
+    {
+        unsupported.push(format!("{}: scripts are not supported", name));
+    }
+}
+
+fn disabled(value: &Value) -> bool {
+    value
+        .get("disabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+fn value_of(value: &Value) -> String {
+    match value.get("value") {
+        None | Some(Value::Null) => String::new(),
+        Some(value) => text(value),
+    }
+}
+
+// the enabled `{key, value}` pairs of the list.
+fn pairs(list: Option<&Value>) -> Vec<(String, String)> {
+    list.and_then(Value::as_array)
+        .map(|list| {
+            list.iter()
+                .filter(|pair| !disabled(pair))
+                .filter_map(|pair| Some((pair.get("key")?.as_str()?.to_string(), value_of(pair))))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn into_request(
+    name: &str,
+    request: &Value,
+    auth: Option<&Value>,
+    unsupported: &mut Vec<String>,
+) -> Result<Request> {
+    // a request may be nothing but its url
+    if let Value::String(url) = request {
+        return into_request(name, &serde_json::json!({ "url": url }), auth, unsupported);
+    }
+    check_unsupported(name, request, unsupported);
+    let method = request
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("GET")
+        .to_uppercase();
+    let (host, path, query) = match request.get("url") {
+        Some(Value::String(raw)) => import::split_raw(raw),
+        Some(url) => url_parts(url),
+        None => bail!("no url"),
+    };
+    let mut headers = pairs(request.get("header"))
+        .into_iter()
+        .map(|(k, v)| (k, Value::String(v)))
+        .collect::<Map<_, _>>();
+    // a null auth is inherited
+    let auth = request
+        .get("auth")
+        .filter(|auth| !auth.is_null())
+        .or(auth)
+        .and_then(|auth| into_auth(name, auth, unsupported));
+    let (body_kind, body) = match request.get("body") {
+        Some(body) => into_body(name, body, &mut headers, unsupported).unzip(),
+        None => (None, None),
+    };
+    Ok(Request {
+        id: Id::new(),
+        name: name.to_string(),
+        method,
+        path,
+        query: import::query(query),
+        host,
+        headers: Value::Object(headers),
+        body_kind: body_kind.unwrap_or_default(),
+        body,
+        assertions: Value::Array(Vec::new()),
+        extractors: Value::Array(Vec::new()),
+        auth: auth.map(|auth| serde_json::json!(auth)),
+        ..Default::default()
+    })
+}
+
+// the auth of a Postman auth object, none for `noauth`.
+fn into_auth(name: &str, auth: &Value, unsupported: &mut Vec<String>) -> Option<Auth> {
+    let kind = auth.get("type").and_then(Value::as_str)?;
+    let params = pairs(auth.get(kind)).into_iter().collect::<HashMap<_, _>>();
+    let param = |key: &str| params.get(key).cloned().unwrap_or_default();
+    let optional = |key: &str| params.get(key).cloned().filter(|value| !value.is_empty());
+    Some(match kind {
+        "noauth" => return None,
+        "basic" => Auth::Basic {
+            username: param("username"),
+            password: param("password"),
+        },
+        "bearer" => Auth::Bearer {
+            token: param("token"),
+        },
+        "apikey" => Auth::ApiKey {
+            name: param("key"),
+            value: param("value"),
+            location: match param("in").as_str() {
+                "query" => ApiKeyLocation::Query,
+                _ => ApiKeyLocation::Header,
+            },
+        },
+        "digest" => Auth::Digest {
+            username: param("username"),
+            password: param("password"),
+        },
+        "awsv4" => Auth::AwsSigV4 {
+            access_key_id: param("accessKey"),
+            secret_access_key: param("secretKey"),
+            session_token: optional("sessionToken"),
+            region: param("region"),
+            service: param("service"),
+        },
+        "oauth2" if param("grant_type") == "client_credentials" => Auth::OAuth2ClientCredentials {
+            token_url: param("accessTokenUrl"),
+            client_id: param("clientId"),
+            client_secret: param("clientSecret"),
+            scope: optional("scope"),
+        },
+        kind => {
+            unsupported.push(format!("{}: auth of type {} is not supported", name, kind));
+            return None;
+        }
+    })
+}
+
+fn default_header(headers: &mut Map<String, Value>, name: &str, value: &str) {
+    if !headers.keys().any(|k| k.eq_ignore_ascii_case(name)) {
+        headers.insert(name.to_string(), Value::String(value.to_string()));
+    }
+}
+
+fn into_body(
+    name: &str,
+    body: &Value,
+    headers: &mut Map<String, Value>,
+    unsupported: &mut Vec<String>,
+) -> Option<(BodyKind, Value)> {
+    if disabled(body) {
+        return None;
+    }
+    match body.get("mode").and_then(Value::as_str).unwrap_or_default() {
+        "raw" => {
+            let raw = body.get("raw").and_then(Value::as_str).unwrap_or_default();
+            let language = body
+                .pointer("/options/raw/language")
+                .and_then(Value::as_str)
+                .unwrap_or("text");
+            let content_type = match language {
+                "json" => "application/json",
+                "xml" => "application/xml",
+                "html" => "text/html",
+                "javascript" => "application/javascript",
+                _ => "text/plain",
+            };
+            default_header(headers, "Content-Type", content_type);
+            // a JSON body is kept as JSON, anything else as text
+            match serde_json::from_str(raw) {
+                Ok(json) if language == "json" => Some((BodyKind::Json, json)),
+                _ => Some((BodyKind::Text, Value::String(raw.to_string()))),
+            }
+        }
+        "urlencoded" => {
+            let form = pairs(body.get("urlencoded"))
+                .into_iter()
+                .map(|(k, v)| (k, Value::String(v)))
+                .collect::<Map<_, _>>();
+            Some((BodyKind::Form, Value::Object(form)))
+        }
+        "formdata" => {
+            let mut parts = Vec::new();
+            for field in body
+                .get("formdata")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter(|field| !disabled(field))
+            {
+                let Some(key) = field.get("key").and_then(Value::as_str) else {
+                    continue;
+                };
+                if field.get("type").and_then(Value::as_str) == Some("file") {
+                    unsupported.push(format!(
+                        "{}: the file of field {} is not imported",
+                        name, key
+                    ));
+                    continue;
+                }
+                parts.push(Part {
+                    name: key.to_string(),
+                    value: Some(value_of(field)),
+                    filename: None,
+                    content_type: field
+                        .get("contentType")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                    data: None,
+                });
+            }
+            Some((BodyKind::Multipart, serde_json::json!(parts)))
+        }
+        "graphql" => {
+            default_header(headers, "Content-Type", "application/json");
+            let graphql = body.get("graphql").cloned().unwrap_or_default();
+            let variables = graphql
+                .get("variables")
+                .and_then(Value::as_str)
+                .and_then(|variables| serde_json::from_str::<Value>(variables).ok())
+                .unwrap_or_default();
+            Some((
+                BodyKind::Json,
+                serde_json::json!({
+                    "query": graphql.get("query").cloned().unwrap_or_default(),
+                    "variables": variables,
+                }),
+            ))
+        }
+        "" => None,
+        mode => {
+            unsupported.push(format!("{}: {} bodies are not supported", name, mode));
+            None
+        }
+    }
+}
+
+// the host, path and query of an url object, whose parts may be variables.
+fn url_parts(url: &Value) -> (String, String, Vec<(String, String)>) {
+    let host = match url.get("host") {
+        Some(Value::Array(parts)) => parts.iter().map(text).collect::<Vec<_>>().join("."),
+        Some(host) => text(host),
+        None => String::new(),
+    };
+    if host.is_empty() {
+        let raw = url.get("raw").and_then(Value::as_str).unwrap_or_default();
+        return import::split_raw(raw);
+    }
+    let host = match url.get("protocol").and_then(Value::as_str) {
+        Some(protocol) => format!("{}://{}", protocol, host),
+        None => import::with_scheme(host),
+    };
+    let host = match url.get("port").and_then(Value::as_str) {
+        Some(port) => format!("{}:{}", host, port),
+        None => host,
+    };
+    // the values of the `:name` segments
+    let variables = pairs(url.get("variable"))
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let segments = match url.get("path") {
+        Some(Value::Array(segments)) => segments
+            .iter()
+            .map(|segment| match segment {
+                Value::Object(segment) => segment.get("value").map(text).unwrap_or_default(),
+                other => text(other),
+            })
+            .collect(),
+        Some(Value::String(path)) => path
+            .trim_start_matches('/')
+            .split('/')
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    };
+    let path = segments
+        .into_iter()
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) => match variables.get(name) {
+                Some(value) if !value.is_empty() => value.clone(),
+                _ => format!("{{{{{}}}}}", name),
+            },
+            None => segment,
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    (host, format!("/{}", path), pairs(url.get("query")))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn collection() -> Value {
+        json!({
+            "info": {
+                "name": "shop",
+                "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+            },
+            "auth": { "type": "bearer", "bearer": [{ "key": "token", "value": "{{token}}" }] },
+            "variable": [
+                { "key": "baseUrl", "value": "https://shop.test" },
+                { "key": "off", "value": "x", "disabled": true }
+            ],
+            "item": [
+                {
+                    "name": "users",
+                    "auth": {
+                        "type": "basic",
+                        "basic": [
+                            { "key": "username", "value": "me" },
+                            { "key": "password", "value": "secret" }
+                        ]
+                    },
+                    "item": [{
+                        "name": "get",
+                        "request": {
+                            "method": "get",
+                            "url": {
+                                "raw": "https://shop.test:8443/users/:id?tag=a&tag=b",
+                                "protocol": "https",
+                                "host": ["shop", "test"],
+                                "port": "8443",
+                                "path": ["users", ":id"],
+                                "variable": [{ "key": "id" }],
+                                "query": [
+                                    { "key": "tag", "value": "a" },
+                                    { "key": "tag", "value": "b" },
+                                    { "key": "off", "value": "c", "disabled": true }
+                                ]
+                            },
+                            "header": [{ "key": "X-Id", "value": "7" }]
+                        }
+                    }]
+                },
+                {
+                    "name": "create",
+                    "event": [{ "listen": "test", "script": { "exec": ["pm.test()"] } }],
+                    "request": {
+                        "method": "POST",
+                        "url": "{{baseUrl}}/orders",
+                        "auth": null,
+                        "body": {
+                            "mode": "raw",
+                            "raw": "{\"id\": 1}",
+                            "options": { "raw": { "language": "json" } }
+                        }
+                    }
+                },
+                {
+                    "name": "upload",
+                    "request": {
+                        "method": "POST",
+                        "url": "{{baseUrl}}/files",
+                        "auth": { "type": "hawk", "hawk": [] },
+                        "body": {
+                            "mode": "formdata",
+                            "formdata": [
+                                { "key": "name", "value": "a.png" },
+                                { "key": "file", "type": "file", "src": "/tmp/a.png" }
+                            ]
+                        }
+                    }
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn map_requests() {
+        let imported = import(&collection()).unwrap();
+        assert_eq!(imported.requests.len(), 3);
+
+        let get = &imported.requests[0];
+        assert_eq!(get.name, "users / get");
+        assert_eq!(get.method, "GET");
+        assert_eq!(get.host, "https://shop.test:8443");
+        // a path variable without a value is left to the environment
+        assert_eq!(get.path, "/users/{{id}}");
+        assert_eq!(get.query, json!({ "tag": ["a", "b"] }));
+        assert_eq!(get.headers, json!({ "X-Id": "7" }));
+        assert_eq!(
+            get.auth,
+            Some(json!(Auth::Basic {
+                username: "me".to_string(),
+                password: "secret".to_string(),
+            }))
+        );
+
+        let create = &imported.requests[1];
+        assert_eq!(create.host, "{{baseUrl}}");
+        assert_eq!(create.path, "/orders");
+        assert_eq!(
+            create.headers,
+            json!({ "Content-Type": "application/json" })
+        );
+        assert_eq!(create.body_kind, BodyKind::Json);
+        assert_eq!(create.body, Some(json!({ "id": 1 })));
+        // the auth of the collection is inherited
+        assert_eq!(
+            create.auth,
+            Some(json!(Auth::Bearer {
+                token: "{{token}}".to_string(),
+            }))
+        );
+
+        let upload = &imported.requests[2];
+        assert_eq!(upload.body_kind, BodyKind::Multipart);
+        assert_eq!(upload.auth, None);
+
+        let environment = imported.environment.unwrap();
+        assert_eq!(environment.name, "shop");
+        assert_eq!(
+            environment.variables,
+            json!({ "baseUrl": "https://shop.test" })
+        );
+    }
+
+    #[test]
+    fn report_what_is_not_supported() {
+        let imported = import(&collection()).unwrap();
+        assert_eq!(
+            imported.unsupported,
+            [
+                "create: scripts are not supported",
+                "upload: auth of type hawk is not supported",
+                "upload: the file of field file is not imported",
+            ]
+        );
+
+        let mut v2 = collection();
+        v2["info"]["schema"] = json!("https://schema.getpostman.com/json/collection/v2.0.0/");
+        assert!(import(&v2).is_err());
+    }
+
+    #[test]
+    fn map_auth() {
+        let mut unsupported = Vec::new();
+        let auth = json!({
+            "type": "oauth2",
+            "oauth2": [
+                { "key": "grant_type", "value": "client_credentials" },
+                { "key": "accessTokenUrl", "value": "https://auth.test/token" },
+                { "key": "clientId", "value": "id" },
+                { "key": "clientSecret", "value": "secret" },
+                { "key": "scope", "value": "" }
+            ]
+        });
+        assert_eq!(
+            into_auth("r", &auth, &mut unsupported).map(|auth| json!(auth)),
+            Some(json!(Auth::OAuth2ClientCredentials {
+                token_url: "https://auth.test/token".to_string(),
+                client_id: "id".to_string(),
+                client_secret: "secret".to_string(),
+                scope: None,
+            }))
+        );
+        let auth = json!({ "type": "apikey", "apikey": [
+            { "key": "key", "value": "X-Key" },
+            { "key": "value", "value": "k" },
+            { "key": "in", "value": "query" }
+        ]});
+        assert_eq!(
+            into_auth("r", &auth, &mut unsupported).map(|auth| json!(auth)),
+            Some(json!(Auth::ApiKey {
+                name: "X-Key".to_string(),
+                value: "k".to_string(),
+                location: ApiKeyLocation::Query,
+            }))
+        );
+        let auth = json!({ "type": "noauth" });
+        assert!(into_auth("r", &auth, &mut unsupported).is_none());
+        let auth = json!({
+            "type": "oauth2",
+            "oauth2": [{ "key": "grant_type", "value": "authorization_code" }]
+        });
+        assert!(into_auth("r", &auth, &mut unsupported).is_none());
+        assert_eq!(unsupported, ["r: auth of type oauth2 is not supported"]);
+    }
+}
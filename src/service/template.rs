@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Local;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use serde_json::Value;
+use sha2::Sha256;
+use uuid::Uuid;
+
+const OPEN: &str = "{{";
+const CLOSE: &str = "}}";
+
+// render the `{{...}}` expressions of a string.
+//
+// expressions starting with `$` are generators producing a fresh value every
+// time they are rendered:
+//
+// - `{{$uuid}}`: a random v4 uuid
+// - `{{$timestamp}}`: the unix timestamp in seconds
+// - `{{$isoNow}}`: the current time in RFC 3339
+// - `{{$randomInt min max}}`: a random integer in `[min, max]`
+// - `{{$base64 text}}`: the base64 of the text
+// - `{{$hmacSha256 key data}}`: the hex HMAC-SHA256 of data signed with key
+//
+// any other expression is the name of a variable, replaced by its value, or
+// left as it is when there is no such variable.
+pub(crate) fn render(input: &str, variables: &HashMap<String, String>) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find(OPEN) {
+        let Some(end) = rest[start..].find(CLOSE) else {
+            break;
+        };
+        let end = start + end;
+        output.push_str(&rest[..start]);
+        let expression = rest[start + OPEN.len()..end].trim();
+        match expression.strip_prefix('$') {
+            Some(generator) => output.push_str(&generate(generator)?),
+            None => match variables.get(expression) {
+                Some(value) => output.push_str(value),
+                None => output.push_str(&rest[start..end + CLOSE.len()]),
+            },
+        }
+        rest = &rest[end + CLOSE.len()..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+// render every string in a JSON value, object keys included.
+pub(crate) fn render_value(value: &Value, variables: &HashMap<String, String>) -> Result<Value> {
+    Ok(match value {
+        Value::String(s) => Value::String(render(s, variables)?),
+        Value::Array(array) => Value::Array(
+            array
+                .iter()
+                .map(|v| render_value(v, variables))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(k, v)| Ok((render(k, variables)?, render_value(v, variables)?)))
+                .collect::<Result<_>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn generate(generator: &str) -> Result<String> {
+    let (name, args) = match generator.split_once(char::is_whitespace) {
+        Some((name, args)) => (name, args.trim()),
+        None => (generator, ""),
+    };
+    let value = match name {
+        "uuid" => Uuid::new_v4().to_string(),
+        "timestamp" => Local::now().timestamp().to_string(),
+        "isoNow" => Local::now().to_rfc3339(),
+        "randomInt" => {
+            let bounds = args
+                .split_whitespace()
+                .map(str::parse::<i64>)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| anyhow!("invalid $randomInt bounds `{}`: {}", args, e))?;
+            let (min, max) = match bounds[..] {
+                [] => (0, i64::from(i32::MAX)),
+                [max] => (0, max),
+                [min, max] => (min, max),
+                _ => bail!("$randomInt takes at most two bounds, got `{}`", args),
+            };
+            if min > max {
+                bail!("invalid $randomInt bounds, {} is greater than {}", min, max);
+            }
+            rand::thread_rng().gen_range(min..=max).to_string()
+        }
+        "base64" => STANDARD.encode(args),
+        "hmacSha256" => {
+            let (key, data) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+            let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())?;
+            mac.update(data.trim_start().as_bytes());
+            mac.finalize()
+                .into_bytes()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect()
+        }
+        unknown => bail!("unknown generator `${}`", unknown),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn render_variables() {
+        let variables = HashMap::from([
+            ("host".to_string(), "example.com".to_string()),
+            ("id".to_string(), "7".to_string()),
+        ]);
+        assert_eq!(
+            render("https://{{host}}/users/{{ id }}?x={{missing}}", &variables).unwrap(),
+            "https://example.com/users/7?x={{missing}}"
+        );
+        assert_eq!(render("{{host", &variables).unwrap(), "{{host");
+        assert_eq!(
+            render_value(&json!({ "{{id}}": ["{{host}}", 1, null] }), &variables).unwrap(),
+            json!({ "7": ["example.com", 1, null] })
+        );
+    }
+
+    #[test]
+    fn generators() {
+        let none = HashMap::new();
+        let uuid = render("{{$uuid}}", &none).unwrap();
+        assert_eq!(Uuid::parse_str(&uuid).unwrap().get_version_num(), 4);
+        assert_ne!(render("{{$uuid}}", &none).unwrap(), uuid);
+
+        let timestamp = render("{{$timestamp}}", &none).unwrap();
+        assert!((timestamp.parse::<i64>().unwrap() - Local::now().timestamp()).abs() < 5);
+        let now = render("{{$isoNow}}", &none).unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(&now).is_ok());
+
+        for _ in 0..20 {
+            let n = render("{{$randomInt -2 2}}", &none).unwrap();
+            assert!((-2..=2).contains(&n.parse::<i64>().unwrap()));
+        }
+        assert_eq!(render("{{$randomInt 3 3}}", &none).unwrap(), "3");
+        assert!(render("{{$randomInt 3 1}}", &none).is_err());
+        assert!(render("{{$randomInt a}}", &none).is_err());
+        assert!(render("{{$randomInt 1 2 3}}", &none).is_err());
+
+        assert_eq!(
+            render("Basic {{$base64 user:pass}}", &none).unwrap(),
+            "Basic dXNlcjpwYXNz"
+        );
+        // RFC 4231, test case 2
+        assert_eq!(
+            render("{{$hmacSha256 Jefe what do ya want for nothing?}}", &none).unwrap(),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert!(render("{{$nope}}", &none).is_err());
+    }
+}
@@ -0,0 +1,38 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+// how long the phases of opening a connection took.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Phases {
+    // none when the host is an address
+    pub(crate) dns: Option<Duration>,
+    // to the host, or through the proxy to it
+    pub(crate) connect: Duration,
+    // none for http
+    pub(crate) tls: Option<Duration>,
+}
+
+// the phases of the connections a client opens, recorded by its connector and
+// taken by the request the connection was opened for.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PhaseTimer {
+    last: Arc<Mutex<Option<Phases>>>,
+}
+
+impl PhaseTimer {
+    pub(crate) fn record(&self, phases: Phases) {
+        *self.last.lock().unwrap() = Some(phases);
+    }
+
+    // the phases of the last connection opened, if there was one since the
+    // last call.
+    pub(crate) fn take(&self) -> Option<Phases> {
+        self.last.lock().unwrap().take()
+    }
+}
+
+pub(crate) fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sqlx_crud::Schema;
+
+use crate::{
+    api::{error::Error, resp::ExpectRowsAffected},
+    entity::{id::Id, variable::Variable},
+    service::event::{Action, Event},
+    storage::{store, Filter, Storage},
+};
+
+// the variables of the workspace by name.
+pub(crate) async fn variables(workspace_id: Id) -> Result<HashMap<String, String>> {
+    Ok(store()
+        .list::<Variable>(Filter::default().and_eq("workspace_id", workspace_id))
+        .await?
+        .into_iter()
+        .map(|variable| (variable.name, variable.value))
+        .collect())
+}
+
+// set the variable of the workspace, creating it when the workspace does not
+// have a variable of the name yet.
+pub(crate) async fn set(
+    workspace_id: Id,
+    name: String,
+    value: String,
+    execution_id: Option<Id>,
+) -> Result<Variable> {
+    let existing = store()
+        .list::<Variable>(
+            Filter::default()
+                .and_eq("workspace_id", workspace_id)
+                .and_eq("name", &name),
+        )
+        .await?
+        .into_iter()
+        .next();
+    let (variable, action) = match existing {
+        Some(variable) => {
+            let variable = Variable {
+                value,
+                execution_id,
+                ..variable
+            };
+            store().update(variable.clone()).await?.expect(1)?;
+            (variable, Action::Updated)
+        }
+        None => {
+            let variable = Variable {
+                id: Id::new(),
+                workspace_id,
+                name,
+                value,
+                execution_id,
+                ..Default::default()
+            };
+            store().create(variable.clone()).await?.expect(1)?;
+            (variable, Action::Created)
+        }
+    };
+    publish(&variable, action);
+    Ok(variable)
+}
+
+// rename the variable of the workspace and set its value, the name must not
+// be the one of another variable of the workspace.
+pub(crate) async fn update(
+    workspace_id: Id,
+    id: Id,
+    name: String,
+    value: String,
+) -> Result<Variable, Error> {
+    let variable = store()
+        .by_id::<Variable>(id)
+        .await?
+        .filter(|variable| variable.workspace_id == workspace_id)
+        .ok_or_else(|| Error::NotFound)?;
+    let taken = store()
+        .list::<Variable>(
+            Filter::default()
+                .and_eq("workspace_id", workspace_id)
+                .and_eq("name", &name),
+        )
+        .await?
+        .iter()
+        .any(|other| other.id != id);
+    if taken {
+        return Err(Error::BadRequest(format!(
+            "the workspace has a variable named {} already",
+            name
+        )));
+    }
+    let variable = Variable {
+        name,
+        value,
+        execution_id: None,
+        ..variable
+    };
+    store().update(variable.clone()).await?.expect(1)?;
+    publish(&variable, Action::Updated);
+    Ok(variable)
+}
+
+fn publish(variable: &Variable, action: Action) {
+    Event::new(
+        Variable::table_name(),
+        action,
+        variable.id.to_string(),
+        variable,
+    )
+    .in_workspace(variable.workspace_id)
+    .publish();
+}
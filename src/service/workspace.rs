@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sqlx_crud::Schema;
+
+use crate::{
+    api::{error::Error, resp::ExpectRowsAffected},
+    entity::{
+        collection::{Collection, CollectionRun},
+        cookie_jar::CookieJar,
+        environment::Environment,
+        execution::{Execution, RawHttpRequest, RawHttpResponse},
+        id::Id,
+        request::Request,
+        variable::Variable,
+        workspace::Workspace,
+    },
+    storage::{store, Filter, Storage},
+};
+
+// copy a workspace with all of its saved requests, environments, cookie jars,
+// collections and variables, executions and collection runs are history of
+// the source workspace and are not copied.
+pub(crate) async fn clone_workspace(
+    source: Id,
+    name: String,
+    description: Option<String>,
+) -> Result<Workspace> {
+    let workspace = Workspace {
+        id: Id::new(),
+        name,
+        description,
+        ..Default::default()
+    };
+    let workspace_id = workspace.id;
+    store().create(workspace).await?.expect(1)?;
+    if let Err(e) = copy_workspace(source, workspace_id).await {
+        // do not leave a partial clone behind
+        delete_workspace(workspace_id).await?;
+        return Err(e);
+    }
+    Ok(store()
+        .by_id::<Workspace>(workspace_id)
+        .await?
+        .ok_or_else(|| Error::NotFound)?)
+}
+
+// copy the entities of the source workspace into the new one.
+async fn copy_workspace(source: Id, workspace_id: Id) -> Result<()> {
+    let requests = store()
+        .list::<Request>(Filter::default().and_eq("workspace_id", source))
+        .await?;
+    tracing::info!(
+        "cloning {} requests from workspace {} into {}",
+        requests.len(),
+        source,
+        workspace_id
+    );
+    // the collections refer to the copies of their requests
+    let mut request_ids = HashMap::new();
+    for request in requests {
+        let id = Id::new();
+        request_ids.insert(request.id, id);
+        let request = Request {
+            id,
+            workspace_id,
+            created_at: None,
+            updated_at: None,
+            deleted_at: None,
+            ..request
+        };
+        store().create(request).await?.expect(1)?;
+    }
+    let environments = store()
+        .list::<Environment>(Filter::default().and_eq("workspace_id", source))
+        .await?;
+    for environment in environments {
+        let environment = Environment {
+            id: Id::new(),
+            workspace_id,
+            created_at: None,
+            updated_at: None,
+            deleted_at: None,
+            ..environment
+        };
+        store().create(environment).await?.expect(1)?;
+    }
+    let jars = store()
+        .list::<CookieJar>(Filter::default().and_eq("workspace_id", source))
+        .await?;
+    for jar in jars {
+        let jar = CookieJar {
+            id: Id::new(),
+            workspace_id,
+            created_at: None,
+            updated_at: None,
+            deleted_at: None,
+            ..jar
+        };
+        store().create(jar).await?.expect(1)?;
+    }
+    let collections = store()
+        .list::<Collection>(Filter::default().and_eq("workspace_id", source))
+        .await?;
+    for collection in collections {
+        let requests = collection
+            .request_ids()
+            .iter()
+            .map(|id| request_ids.get(id).copied().unwrap_or(*id))
+            .collect::<Vec<_>>();
+        let collection = Collection {
+            id: Id::new(),
+            workspace_id,
+            requests: serde_json::json!(requests),
+            created_at: None,
+            updated_at: None,
+            deleted_at: None,
+            ..collection
+        };
+        store().create(collection).await?.expect(1)?;
+    }
+    let variables = store()
+        .list::<Variable>(Filter::default().and_eq("workspace_id", source))
+        .await?;
+    for variable in variables {
+        let variable = Variable {
+            id: Id::new(),
+            workspace_id,
+            execution_id: None,
+            created_at: None,
+            updated_at: None,
+            deleted_at: None,
+            ..variable
+        };
+        store().create(variable).await?.expect(1)?;
+    }
+    Ok(())
+}
+
+// delete a workspace together with its requests, environments, cookie jars,
+// collections, variables, executions and collection runs, returns the number
+// of workspaces deleted.
+pub(crate) async fn delete_workspace(workspace_id: Id) -> Result<u64, Error> {
+    let mut in_workspace = Filter::default();
+    in_workspace.and_eq("workspace_id", workspace_id);
+    let mut workspace = Filter::default();
+    workspace.and_eq("id", workspace_id);
+    // the raw requests and responses have no workspace, they are found by the
+    // executions referring to them
+    let executions = store().list::<Execution>(&in_workspace).await?;
+    let mut deletes = Vec::new();
+    if !executions.is_empty() {
+        let mut requests = Filter::default();
+        requests.and_in(
+            "id",
+            &executions.iter().map(|e| e.request).collect::<Vec<_>>(),
+        );
+        let mut responses = Filter::default();
+        responses.and_in(
+            "id",
+            &executions.iter().map(|e| e.response).collect::<Vec<_>>(),
+        );
+        deletes.push((RawHttpRequest::table_name(), requests));
+        deletes.push((RawHttpResponse::table_name(), responses));
+    }
+    deletes.extend([
+        (Execution::table_name(), in_workspace.clone()),
+        (Request::table_name(), in_workspace.clone()),
+        (CollectionRun::table_name(), in_workspace.clone()),
+        (Collection::table_name(), in_workspace.clone()),
+        (Variable::table_name(), in_workspace.clone()),
+        (CookieJar::table_name(), in_workspace.clone()),
+        (Environment::table_name(), in_workspace),
+        (Workspace::table_name(), workspace),
+    ]);
+    let rows_affected = store().delete_where(&deletes).await?;
+    // the last statement deletes the workspace itself
+    Ok(rows_affected.last().copied().unwrap_or_default())
+}
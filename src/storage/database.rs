@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use sql_builder::SqlBuilder;
+use sqlx::Row;
+
+use crate::{db::DbPool, entity::id::Id};
+
+use super::{Condition, Entity, Filter, Storage};
+
+// evaluate the expression with `$pool` bound to the pool of the database
+// driver, the expression is expanded once per driver so it has to compile for
+// all of them.
+macro_rules! with_pool {
+    ($db:expr, $pool:ident => $body:expr) => {
+        match $db {
+            DbPool::MySql($pool) => $body,
+            DbPool::Sqlite($pool) => $body,
+            DbPool::Postgres($pool) => $body,
+        }
+    };
+}
+
+fn apply_conditions(builder: &mut SqlBuilder, filter: &Filter) {
+    for condition in filter.conditions.iter() {
+        match condition {
+            Condition::Eq(column, value) => {
+                builder.and_where_eq(column, sql_builder::quote(value));
+            }
+            Condition::In(column, values) => {
+                builder.and_where_in_quoted(column, values);
+            }
+            Condition::Contains(column, text) => {
+                builder.and_where_like(column, format!("%{}%", text));
+            }
+        }
+    }
+}
+
+fn select_sql(table: &str, filter: &Filter) -> sqlx::Result<String> {
+    let mut builder = SqlBuilder::select_from(table);
+    apply_conditions(&mut builder, filter);
+    for (column, desc) in filter.order.iter() {
+        builder.order_by(column, *desc);
+    }
+    if let Some((offset, limit)) = filter.page {
+        builder.offset(offset).limit(limit);
+    }
+    builder
+        .sql()
+        .map_err(|e| sqlx::Error::Protocol(e.to_string()))
+}
+
+#[async_trait]
+impl Storage for DbPool {
+    async fn by_id<E: Entity>(&self, id: Id) -> sqlx::Result<Option<E>> {
+        with_pool!(self, pool => E::by_id(pool, id).await)
+    }
+
+    async fn list<E: Entity>(&self, filter: &Filter) -> sqlx::Result<Vec<E>> {
+        let sql = select_sql(E::table_name(), filter)?;
+        with_pool!(self, pool => sqlx::query_as::<_, E>(&sql).fetch_all(pool).await)
+    }
+
+    async fn count<E: Entity>(&self, filter: &Filter) -> sqlx::Result<i64> {
+        // without the order, postgres refuses to order an aggregate
+        let mut builder = SqlBuilder::select_from(E::table_name());
+        apply_conditions(&mut builder, filter);
+        let sql = builder
+            .count("0")
+            .sql()
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+        with_pool!(self, pool => sqlx::query(&sql).fetch_one(pool).await?.try_get(0))
+    }
+
+    async fn create<E: Entity>(&self, entity: E) -> sqlx::Result<u64> {
+        with_pool!(self, pool => entity.create(pool).await.map(|r| r.rows_affected()))
+    }
+
+    async fn update<E: Entity>(&self, entity: E) -> sqlx::Result<u64> {
+        with_pool!(self, pool => entity.update(pool).await.map(|r| r.rows_affected()))
+    }
+
+    async fn delete<E: Entity>(&self, entity: E) -> sqlx::Result<u64> {
+        with_pool!(self, pool => entity.delete(pool).await.map(|r| r.rows_affected()))
+    }
+
+    async fn increment<E: Entity>(
+        &self,
+        id: Id,
+        column: &'static str,
+        _increment: for<'a> fn(&'a mut E),
+    ) -> sqlx::Result<u64> {
+        let mut builder = SqlBuilder::update_table(E::table_name());
+        builder.set(column, format!("{} + 1", column));
+        apply_conditions(&mut builder, Filter::default().and_eq("id", id));
+        let sql = builder
+            .sql()
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+        with_pool!(self, pool => sqlx::query(&sql).execute(pool).await.map(|r| r.rows_affected()))
+    }
+
+    async fn delete_where(&self, deletes: &[(&'static str, Filter)]) -> sqlx::Result<Vec<u64>> {
+        let statements = deletes
+            .iter()
+            .map(|(table, filter)| {
+                let mut builder = SqlBuilder::delete_from(table);
+                apply_conditions(&mut builder, filter);
+                builder
+                    .sql()
+                    .map_err(|e| sqlx::Error::Protocol(e.to_string()))
+            })
+            .collect::<sqlx::Result<Vec<_>>>()?;
+        with_pool!(self, pool => {
+            let mut tx = pool.begin().await?;
+            let mut rows_affected = Vec::with_capacity(statements.len());
+            for sql in statements.iter() {
+                rows_affected.push(sqlx::query(sql).execute(&mut tx).await?.rows_affected());
+            }
+            tx.commit().await?;
+            Ok(rows_affected)
+        })
+    }
+}
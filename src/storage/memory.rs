@@ -0,0 +1,385 @@
+use std::{
+    any::Any,
+    cmp::Ordering,
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx_crud::Schema;
+
+use crate::entity::{
+    bin::TrappedRequest,
+    collection::CollectionRun,
+    execution::{Execution, RawHttpRequest, RawHttpResponse},
+    id::Id,
+};
+
+use super::{Condition, Entity, Filter, Storage};
+
+struct Row {
+    id: Id,
+    // the entity as JSON, which is what the filters are evaluated on
+    value: Value,
+    entity: Box<dyn Any + Send + Sync>,
+}
+
+impl Row {
+    fn new<E: Entity>(entity: E) -> Self {
+        Self {
+            id: entity.id(),
+            value: serde_json::to_value(&entity).unwrap_or(Value::Null),
+            entity: Box::new(entity),
+        }
+    }
+
+    fn entity<E: Entity>(&self) -> Option<E> {
+        self.entity.downcast_ref::<E>().cloned()
+    }
+
+    fn text(&self, column: &str) -> Option<String> {
+        match self.value.get(column)? {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    fn matches(&self, filter: &Filter) -> bool {
+        filter.conditions.iter().all(|condition| match condition {
+            Condition::Eq(column, value) => self.text(column).as_ref() == Some(value),
+            Condition::In(column, values) => {
+                self.text(column).is_some_and(|text| values.contains(&text))
+            }
+            Condition::Contains(column, text) => self
+                .text(column)
+                .is_some_and(|s| s.to_lowercase().contains(&text.to_lowercase())),
+        })
+    }
+}
+
+// null first, then numbers by value and anything else by its text.
+fn compare(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(Value::String(a)), Some(Value::String(b))) => a.cmp(b),
+        (None | Some(Value::Null), None | Some(Value::Null)) => Ordering::Equal,
+        (None | Some(Value::Null), _) => Ordering::Less,
+        (_, None | Some(Value::Null)) => Ordering::Greater,
+        (Some(a), Some(b)) => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+// the tables holding history, the only ones `capacity` applies to. the raw
+// requests and responses go with their executions, and whatever else is
+// configuration which is never dropped.
+fn is_history(table: &str) -> bool {
+    table == Execution::table_name()
+        || table == CollectionRun::table_name()
+        || table == TrappedRequest::table_name()
+}
+
+// keeps the entities of each table in memory, dropping the oldest executions,
+// collection runs and trapped requests once there are `capacity` of them.
+pub(crate) struct MemoryStorage {
+    capacity: usize,
+    tables: Mutex<HashMap<&'static str, VecDeque<Row>>>,
+}
+
+impl std::fmt::Debug for MemoryStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryStorage")
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+impl MemoryStorage {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            tables: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // the rows of the table matching the filter, ordered and paged.
+    fn select<E: Entity, T>(&self, filter: &Filter, f: impl Fn(&Row) -> T) -> Vec<T> {
+        let tables = self.tables.lock().unwrap();
+        let Some(table) = tables.get(E::table_name()) else {
+            return Vec::new();
+        };
+        let mut rows = table
+            .iter()
+            .filter(|row| row.matches(filter))
+            .collect::<Vec<_>>();
+        // the sort is stable, rows comparing equal stay in insertion order
+        rows.sort_by(|a, b| {
+            filter
+                .order
+                .iter()
+                .map(|(column, desc)| {
+                    let ordering = compare(a.value.get(column), b.value.get(column));
+                    if *desc {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        let (offset, limit) = filter.page.unwrap_or((0, usize::MAX));
+        rows.into_iter().skip(offset).take(limit).map(f).collect()
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn by_id<E: Entity>(&self, id: Id) -> sqlx::Result<Option<E>> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables
+            .get(E::table_name())
+            .and_then(|table| table.iter().find(|row| row.id == id))
+            .and_then(Row::entity))
+    }
+
+    async fn list<E: Entity>(&self, filter: &Filter) -> sqlx::Result<Vec<E>> {
+        Ok(self
+            .select::<E, _>(filter, Row::entity)
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    async fn count<E: Entity>(&self, filter: &Filter) -> sqlx::Result<i64> {
+        let filter = Filter {
+            page: None,
+            ..filter.clone()
+        };
+        Ok(self.select::<E, _>(&filter, |_| ()).len() as i64)
+    }
+
+    async fn create<E: Entity>(&self, entity: E) -> sqlx::Result<u64> {
+        let mut tables = self.tables.lock().unwrap();
+        let table = tables.entry(E::table_name()).or_default();
+        let evicted = if is_history(E::table_name()) && table.len() >= self.capacity {
+            table.pop_front()
+        } else {
+            None
+        };
+        table.push_back(Row::new(entity));
+        if let Some(row) = evicted.filter(|_| E::table_name() == Execution::table_name()) {
+            // the raw request and response of the execution go with it
+            for (table, column) in [
+                (RawHttpRequest::table_name(), "request"),
+                (RawHttpResponse::table_name(), "response"),
+            ] {
+                let id = row.text(column);
+                if let Some(table) = tables.get_mut(table) {
+                    table.retain(|raw| Some(raw.id.to_string()) != id);
+                }
+            }
+        }
+        Ok(1)
+    }
+
+    async fn update<E: Entity>(&self, entity: E) -> sqlx::Result<u64> {
+        let mut tables = self.tables.lock().unwrap();
+        let id = entity.id();
+        let row = tables
+            .get_mut(E::table_name())
+            .and_then(|table| table.iter_mut().find(|row| row.id == id));
+        Ok(match row {
+            Some(row) => {
+                *row = Row::new(entity);
+                1
+            }
+            None => 0,
+        })
+    }
+
+    async fn delete<E: Entity>(&self, entity: E) -> sqlx::Result<u64> {
+        let mut tables = self.tables.lock().unwrap();
+        let id = entity.id();
+        let Some(table) = tables.get_mut(E::table_name()) else {
+            return Ok(0);
+        };
+        let len = table.len();
+        table.retain(|row| row.id != id);
+        Ok((len - table.len()) as u64)
+    }
+
+    async fn increment<E: Entity>(
+        &self,
+        id: Id,
+        _column: &'static str,
+        increment: for<'a> fn(&'a mut E),
+    ) -> sqlx::Result<u64> {
+        // the lock is held from reading the entity to writing it back
+        let mut tables = self.tables.lock().unwrap();
+        let row = tables
+            .get_mut(E::table_name())
+            .and_then(|table| table.iter_mut().find(|row| row.id == id));
+        let Some(row) = row else {
+            return Ok(0);
+        };
+        let Some(mut entity) = row.entity::<E>() else {
+            return Ok(0);
+        };
+        increment(&mut entity);
+        *row = Row::new(entity);
+        Ok(1)
+    }
+
+    async fn delete_where(&self, deletes: &[(&'static str, Filter)]) -> sqlx::Result<Vec<u64>> {
+        let mut tables = self.tables.lock().unwrap();
+        Ok(deletes
+            .iter()
+            .map(|(table, filter)| match tables.get_mut(table) {
+                Some(table) => {
+                    let len = table.len();
+                    table.retain(|row| !row.matches(filter));
+                    (len - table.len()) as u64
+                }
+                None => 0,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::entity::workspace::Workspace;
+
+    use super::*;
+
+    fn workspace(name: &str, description: Option<&str>) -> Workspace {
+        Workspace {
+            id: Id::new(),
+            name: name.to_string(),
+            description: description.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn names(workspaces: Vec<Workspace>) -> Vec<String> {
+        workspaces.into_iter().map(|w| w.name).collect()
+    }
+
+    #[tokio::test]
+    async fn filter_order_and_page() {
+        let storage = MemoryStorage::new(10);
+        for (name, description) in [
+            ("beta", Some("second")),
+            ("Alpha", None),
+            ("gamma", Some("third")),
+            ("alphabet", Some("fourth")),
+        ] {
+            storage.create(workspace(name, description)).await.unwrap();
+        }
+        let mut filter = Filter::default();
+        filter.and_contains("name", "ALPHA").order_asc("name");
+        assert_eq!(
+            names(storage.list(&filter).await.unwrap()),
+            ["Alpha", "alphabet"]
+        );
+        let mut filter = Filter::default();
+        filter
+            .and_in("name", &["beta", "gamma", "delta"])
+            .order_desc("name");
+        assert_eq!(
+            names(storage.list(&filter).await.unwrap()),
+            ["gamma", "beta"]
+        );
+        // null sorts first
+        let mut filter = Filter::default();
+        filter.order_asc("description").page(0, 2);
+        assert_eq!(
+            names(storage.list(&filter).await.unwrap()),
+            ["Alpha", "alphabet"]
+        );
+        assert_eq!(storage.count::<Workspace>(&filter).await.unwrap(), 4);
+        let mut filter = Filter::default();
+        filter.and_eq("description", "third");
+        assert_eq!(names(storage.list(&filter).await.unwrap()), ["gamma"]);
+    }
+
+    fn execution(workspace_id: Id) -> (Execution, RawHttpRequest, RawHttpResponse) {
+        let request = RawHttpRequest {
+            id: Id::new(),
+            method: "GET".to_string(),
+            url: "http://localhost/".to_string(),
+            version: "HTTP/1.1".to_string(),
+            headers: json!({}),
+            body_kind: Default::default(),
+            body: None,
+        };
+        let response = RawHttpResponse {
+            id: Id::new(),
+            version: "HTTP/1.1".to_string(),
+            status_code: 200,
+            status_message: "OK".to_string(),
+            headers: json!({}),
+            size: 0,
+            content_type: "text/plain".to_string(),
+            body: Value::Null,
+            raw_body: Vec::new(),
+            truncated: false,
+        };
+        let execution = Execution {
+            id: Id::new(),
+            workspace_id,
+            request: request.id,
+            response: response.id,
+            ..Default::default()
+        };
+        (execution, request, response)
+    }
+
+    #[tokio::test]
+    async fn evict_only_history_with_its_raw_rows() {
+        let storage = MemoryStorage::new(2);
+        let workspaces = (0..3)
+            .map(|i| workspace(&i.to_string(), None))
+            .collect::<Vec<_>>();
+        for w in workspaces.iter() {
+            storage.create(w.clone()).await.unwrap();
+        }
+        let mut executions = Vec::new();
+        for _ in 0..3 {
+            let (execution, request, response) = execution(workspaces[0].id);
+            storage.create(request).await.unwrap();
+            storage.create(response).await.unwrap();
+            storage.create(execution.clone()).await.unwrap();
+            executions.push(execution);
+        }
+        let all = Filter::default();
+        // configuration is kept whatever the capacity
+        assert_eq!(storage.count::<Workspace>(&all).await.unwrap(), 3);
+        assert_eq!(storage.count::<Execution>(&all).await.unwrap(), 2);
+        assert_eq!(storage.count::<RawHttpRequest>(&all).await.unwrap(), 2);
+        assert_eq!(storage.count::<RawHttpResponse>(&all).await.unwrap(), 2);
+        let first = &executions[0];
+        assert!(storage
+            .by_id::<Execution>(first.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(storage
+            .by_id::<RawHttpRequest>(first.request)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(storage
+            .by_id::<RawHttpResponse>(executions[2].response)
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
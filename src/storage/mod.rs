@@ -0,0 +1,230 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use sqlx::{
+    mysql::MySqlRow, postgres::PgRow, sqlite::SqliteRow, FromRow, MySqlPool, PgPool, SqlitePool,
+};
+use sqlx_crud::{Crud, Schema};
+
+use crate::{
+    config::global_config,
+    db::{self, DbPool},
+    entity::{
+        id::Id,
+        workspace::{Workspace, DEFAULT_WORKSPACE},
+    },
+};
+
+pub(crate) mod database;
+pub(crate) mod memory;
+
+use memory::MemoryStorage;
+
+static STORE: OnceCell<Store> = OnceCell::new();
+
+// an entity that can be kept by every storage.
+pub(crate) trait Entity:
+    Schema<Id = Id>
+    + Serialize
+    + Clone
+    + Send
+    + Sync
+    + Unpin
+    + 'static
+    + for<'e> Crud<'e, &'e MySqlPool>
+    + for<'e> Crud<'e, &'e SqlitePool>
+    + for<'e> Crud<'e, &'e PgPool>
+    + for<'r> FromRow<'r, MySqlRow>
+    + for<'r> FromRow<'r, SqliteRow>
+    + for<'r> FromRow<'r, PgRow>
+{
+}
+
+impl<T> Entity for T where
+    T: Schema<Id = Id>
+        + Serialize
+        + Clone
+        + Send
+        + Sync
+        + Unpin
+        + 'static
+        + for<'e> Crud<'e, &'e MySqlPool>
+        + for<'e> Crud<'e, &'e SqlitePool>
+        + for<'e> Crud<'e, &'e PgPool>
+        + for<'r> FromRow<'r, MySqlRow>
+        + for<'r> FromRow<'r, SqliteRow>
+        + for<'r> FromRow<'r, PgRow>
+{
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Condition {
+    Eq(&'static str, String),
+    In(&'static str, Vec<String>),
+    // the column contains the text
+    Contains(&'static str, String),
+}
+
+// which entities to fetch and in which order, the database storage turns it
+// into SQL and the memory storage evaluates it on the serialized entities.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Filter {
+    pub(crate) conditions: Vec<Condition>,
+    // the columns to order by, descending when the flag is set
+    pub(crate) order: Vec<(&'static str, bool)>,
+    // offset and limit
+    pub(crate) page: Option<(usize, usize)>,
+}
+
+impl Filter {
+    pub(crate) fn and_eq<T: ToString>(&mut self, column: &'static str, value: T) -> &mut Self {
+        self.conditions
+            .push(Condition::Eq(column, value.to_string()));
+        self
+    }
+
+    pub(crate) fn and_in<T: ToString>(&mut self, column: &'static str, values: &[T]) -> &mut Self {
+        let values = values.iter().map(ToString::to_string).collect();
+        self.conditions.push(Condition::In(column, values));
+        self
+    }
+
+    pub(crate) fn and_contains<T: ToString>(&mut self, column: &'static str, text: T) -> &mut Self {
+        self.conditions
+            .push(Condition::Contains(column, text.to_string()));
+        self
+    }
+
+    pub(crate) fn order_asc(&mut self, column: &'static str) -> &mut Self {
+        self.order.push((column, false));
+        self
+    }
+
+    pub(crate) fn order_desc(&mut self, column: &'static str) -> &mut Self {
+        self.order.push((column, true));
+        self
+    }
+
+    pub(crate) fn page(&mut self, offset: usize, limit: usize) -> &mut Self {
+        self.page = Some((offset, limit));
+        self
+    }
+}
+
+// where the entities are kept.
+//
+// all methods report the rows affected the way the database does, so the
+// callers check them with `ExpectRowsAffected` whatever the storage is.
+#[async_trait]
+pub(crate) trait Storage {
+    async fn by_id<E: Entity>(&self, id: Id) -> sqlx::Result<Option<E>>;
+    async fn list<E: Entity>(&self, filter: &Filter) -> sqlx::Result<Vec<E>>;
+    // the number of entities matching the filter, regardless of its page
+    async fn count<E: Entity>(&self, filter: &Filter) -> sqlx::Result<i64>;
+    async fn create<E: Entity>(&self, entity: E) -> sqlx::Result<u64>;
+    async fn update<E: Entity>(&self, entity: E) -> sqlx::Result<u64>;
+    async fn delete<E: Entity>(&self, entity: E) -> sqlx::Result<u64>;
+    // add one to the column of the entity at once, so concurrent increments
+    // are not lost. the memory storage has `increment` do it to the entity.
+    async fn increment<E: Entity>(
+        &self,
+        id: Id,
+        column: &'static str,
+        increment: for<'a> fn(&'a mut E),
+    ) -> sqlx::Result<u64>;
+    // delete whatever matches the filter of each table, all at once.
+    async fn delete_where(&self, deletes: &[(&'static str, Filter)]) -> sqlx::Result<Vec<u64>>;
+}
+
+#[derive(Debug)]
+pub(crate) enum Store {
+    Database(DbPool),
+    Memory(MemoryStorage),
+}
+
+macro_rules! dispatch {
+    ($self:ident.$method:ident$(::<$type:ty>)?($($arg:expr),*)) => {
+        match $self {
+            Store::Database(storage) => storage.$method$(::<$type>)?($($arg),*).await,
+            Store::Memory(storage) => storage.$method$(::<$type>)?($($arg),*).await,
+        }
+    };
+}
+
+#[async_trait]
+impl Storage for Store {
+    async fn by_id<E: Entity>(&self, id: Id) -> sqlx::Result<Option<E>> {
+        dispatch!(self.by_id::<E>(id))
+    }
+
+    async fn list<E: Entity>(&self, filter: &Filter) -> sqlx::Result<Vec<E>> {
+        dispatch!(self.list::<E>(filter))
+    }
+
+    async fn count<E: Entity>(&self, filter: &Filter) -> sqlx::Result<i64> {
+        dispatch!(self.count::<E>(filter))
+    }
+
+    async fn create<E: Entity>(&self, entity: E) -> sqlx::Result<u64> {
+        dispatch!(self.create(entity))
+    }
+
+    async fn update<E: Entity>(&self, entity: E) -> sqlx::Result<u64> {
+        dispatch!(self.update(entity))
+    }
+
+    async fn delete<E: Entity>(&self, entity: E) -> sqlx::Result<u64> {
+        dispatch!(self.delete(entity))
+    }
+
+    async fn increment<E: Entity>(
+        &self,
+        id: Id,
+        column: &'static str,
+        increment: for<'a> fn(&'a mut E),
+    ) -> sqlx::Result<u64> {
+        dispatch!(self.increment::<E>(id, column, increment))
+    }
+
+    async fn delete_where(&self, deletes: &[(&'static str, Filter)]) -> sqlx::Result<Vec<u64>> {
+        dispatch!(self.delete_where(deletes))
+    }
+}
+
+// keep everything in the configured database, or in memory when `ephemeral`
+// gives the number of executions, collection runs and trapped requests kept.
+pub(crate) async fn init_storage(ephemeral: Option<usize>) -> Result<()> {
+    let store = match ephemeral {
+        Some(capacity) => {
+            let storage = MemoryStorage::new(capacity);
+            // the migrations create the default workspace in the database
+            storage
+                .create(Workspace {
+                    id: DEFAULT_WORKSPACE,
+                    name: "default".to_string(),
+                    description: Some("the default workspace".to_string()),
+                    ..Default::default()
+                })
+                .await?;
+            tracing::info!(
+                "keep at most {} of each kind of history in memory.",
+                capacity
+            );
+            Store::Memory(storage)
+        }
+        None => {
+            let pool = db::connect().await?;
+            if global_config().db.auto_migrate {
+                pool.migrate().await?;
+            }
+            Store::Database(pool)
+        }
+    };
+    STORE.set(store).expect("set global storage failed.");
+    Ok(())
+}
+
+pub(crate) fn store() -> &'static Store {
+    STORE.get().expect("get global storage failed.")
+}